@@ -0,0 +1,39 @@
+//! Renders a custom scene to a PNG image.
+//!
+//! ```text
+//! cargo run --example still -- still.png
+//! ```
+
+use std::{env, fs::File, io::BufWriter};
+
+use shapes::prelude::*;
+
+fn main() -> std::io::Result<()> {
+    let path = env::args().nth(1).unwrap_or_else(|| "still.png".to_owned());
+
+    let torus = Torus {
+        radius_major: 0.8,
+        radius_minor: 0.3,
+    }
+    .texture(Stripes {
+        colors: [Color::MAGENTA, Color::WHITE],
+        count: 12.0,
+    })
+    .transform(Matrix::rotation_x(0.9));
+    let scene = cube()
+        .texture(Checker {
+            colors: [Color::CYAN, Color::BLUE],
+            size: (4.0, 4.0),
+        })
+        .transform(Matrix::rotation_z(0.6))
+        .transform(Matrix::rotation_x(0.4))
+        .transform(Translation(Vector(-0.9, 0.0, 0.0)))
+        .and(torus.transform(Translation(Vector(1.1, 0.0, 0.0))))
+        .transform(Translation(Vector(0.0, 3.5, 0.0)));
+
+    let viewport = Viewport::new(480, 320, Camera::new(0.5, 1.5, 1.0));
+    let image = viewport.rasterize(scene).to_image(Color(0.1, 0.1, 0.15));
+    encode::png::encode(BufWriter::new(File::create(&path)?), &image)?;
+    println!("wrote {}", path);
+    Ok(())
+}
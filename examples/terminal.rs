@@ -0,0 +1,28 @@
+//! Prints a single frame of a custom scene to the terminal with 256 colors.
+//!
+//! ```text
+//! cargo run --example terminal
+//! ```
+
+use std::io;
+
+use shapes::prelude::*;
+
+fn main() -> io::Result<()> {
+    let scene = cube()
+        .transform(Matrix::rotation_z(0.7))
+        .transform(Matrix::rotation_x(0.5))
+        .transform(Translation(Vector(0.0, 3.0, 0.0)));
+
+    // Terminal cells are about twice as tall as they are wide, and half blocks draw two
+    // rows of cells on each line.
+    let (columns, lines) = (60, 24);
+    let camera = Camera::new(0.5, columns as f64 / (2 * lines) as f64, 1.0);
+    let framebuffer = Viewport::new(columns, 2 * lines, camera).rasterize(scene);
+    encode::ansi::encode(
+        io::stdout().lock(),
+        &framebuffer,
+        RenderMode::HalfBlock,
+        ColorMode::Ansi256,
+    )
+}
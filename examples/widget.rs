@@ -0,0 +1,38 @@
+//! Draws a spinning cube into a corner of a text dashboard for a few seconds.
+//!
+//! ```text
+//! cargo run --example widget
+//! ```
+
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use shapes::prelude::*;
+
+fn main() -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut widget = Widget::new(44, 1, 24, 12);
+    widget.render_mode = RenderMode::HalfBlock;
+
+    write!(stdout, "\x1b[2J")?;
+    for frame in 0..60 {
+        // Redraw the dashboard under the widget, which only draws where the cube is.
+        write!(stdout, "\x1b[H")?;
+        for line in 0..14 {
+            writeln!(
+                stdout,
+                "{:<40}{}",
+                format!(" service {:>2}: ok", line),
+                ".".repeat(28)
+            )?;
+        }
+        widget.draw(&mut stdout, Scene::Cube.at(frame as f64 / 20.0))?;
+        write!(stdout, "\x1b[15;1H")?;
+        stdout.flush()?;
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use crate::{Color, Easing, Vector};
+
+/// A value that can be animated by a [`Track`], by blending between keyframes.
+pub trait Animate: Copy {
+    /// Returns the value `t` of the way from this value to `other`, which is this value
+    /// when `t` is 0 and `other` when `t` is 1.
+    fn lerp(self, other: Self, t: f64) -> Self;
+}
+
+impl Animate for f64 {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Animate for Vector {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Animate for Color {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+/// What a [`Track`] does outside of the time between its first and last keyframes.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Repeat {
+    /// Holding the value of the first keyframe before it and of the last one after it.
+    #[default]
+    Hold,
+    /// Starting again from the first keyframe after reaching the last.
+    Loop,
+    /// Going back from the last keyframe to the first, and then forward again.
+    PingPong,
+}
+
+/// A value that a [`Track`] reaches at a time.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Keyframe<T> {
+    /// The time of the keyframe, in seconds.
+    pub time: f64,
+    /// The value at the time of the keyframe.
+    pub value: T,
+    /// How the value eases into this keyframe from the one before it.
+    pub easing: Easing,
+}
+
+/// A value animated over time by keyframes, such as an angle of rotation or a color.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Track<T> {
+    /// The keyframes, in order of time. There is always at least one.
+    keyframes: Vec<Keyframe<T>>,
+    /// What the track does outside of the time of its keyframes.
+    pub repeat: Repeat,
+}
+
+impl<T: Animate> Track<T> {
+    /// Returns a track that holds `value`, starting from a keyframe at time 0.
+    pub fn new(value: T) -> Self {
+        Self {
+            keyframes: vec![Keyframe {
+                time: 0.0,
+                value,
+                easing: Easing::Linear,
+            }],
+            repeat: Repeat::Hold,
+        }
+    }
+
+    /// Returns this track with a keyframe reaching `value` at `time`, eased by
+    /// `easing` from the keyframe before it.
+    pub fn key(mut self, time: f64, value: T, easing: Easing) -> Self {
+        self.insert(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    /// Returns this track repeating as `repeat` says.
+    pub fn repeat(self, repeat: Repeat) -> Self {
+        Self { repeat, ..self }
+    }
+
+    /// Adds `keyframe` to this track, after any keyframes at the same time.
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        let index = self
+            .keyframes
+            .partition_point(|other| other.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    /// Returns the keyframes of this track, in order of time.
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// Returns the value of this track at `time`.
+    pub fn sample(&self, time: f64) -> T {
+        let first = &self.keyframes[0];
+        let last = &self.keyframes[self.keyframes.len() - 1];
+        let duration = last.time - first.time;
+        if duration <= 0.0 || !time.is_finite() {
+            return last.value;
+        }
+
+        let time = match self.repeat {
+            Repeat::Hold => time.clamp(first.time, last.time),
+            Repeat::Loop => first.time + (time - first.time).rem_euclid(duration),
+            Repeat::PingPong => {
+                let time = (time - first.time).rem_euclid(2.0 * duration);
+                first.time + duration - (time - duration).abs()
+            }
+        };
+        let index = self.keyframes.partition_point(|other| other.time <= time);
+        match (
+            self.keyframes.get(index.wrapping_sub(1)),
+            self.keyframes.get(index),
+        ) {
+            (Some(from), Some(to)) => {
+                let x = (time - from.time) / (to.time - from.time);
+                from.value.lerp(to.value, to.easing.ease(x))
+            }
+            (Some(keyframe), None) | (None, Some(keyframe)) => keyframe.value,
+            (None, None) => unreachable!("tracks always have a keyframe"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Repeat, Track};
+    use crate::{util::check_about, Color, Easing, EasingCurve};
+
+    #[test]
+    fn sample() {
+        let track = Track::new(1.0).key(2.0, 3.0, Easing::Linear).key(
+            3.0,
+            2.0,
+            Easing::InOut(EasingCurve::Sin),
+        );
+        check_about(track.sample(-1.0), 1.0);
+        check_about(track.sample(1.0), 2.0);
+        check_about(track.sample(2.0), 3.0);
+        check_about(track.sample(2.25), 2.8536);
+        check_about(track.sample(2.5), 2.5);
+        check_about(track.sample(7.0), 2.0);
+
+        let track = track.repeat(Repeat::Loop);
+        check_about(track.sample(4.0), 2.0);
+        check_about(track.sample(-0.5), 2.5);
+
+        let track = track.repeat(Repeat::PingPong);
+        check_about(track.sample(4.0), 3.0);
+        check_about(track.sample(5.5), 1.5);
+        check_about(track.sample(6.0), 1.0);
+        check_about(track.sample(-1.0), 2.0);
+    }
+
+    #[test]
+    fn keyframes() {
+        let track = Track::new(Color::BLACK)
+            .key(2.0, Color::WHITE, Easing::Linear)
+            .key(1.0, Color::RED, Easing::Linear);
+        let times: Vec<_> = track.keyframes().iter().map(|key| key.time).collect();
+        assert_eq!(times, [0.0, 1.0, 2.0]);
+        assert_eq!(track.sample(1.5), Color(1.0, 0.5, 0.5));
+
+        // A keyframe at the same time as another jumps to its value.
+        let track = Track::new(0.0)
+            .key(1.0, 1.0, Easing::Linear)
+            .key(1.0, 5.0, Easing::Linear);
+        check_about(track.sample(0.999), 0.999);
+        check_about(track.sample(1.0), 5.0);
+        check_about(Track::new(4.0).repeat(Repeat::Loop).sample(9.0), 4.0);
+    }
+}
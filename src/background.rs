@@ -0,0 +1,172 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::{random::Random, Animate, Color, Easing, ImageTexture, Vector};
+
+/// What is seen where rays hit nothing else in a scene, applied by
+/// [`HittableExt::background`](crate::HittableExt::background).
+///
+/// Backgrounds are infinitely far away, so they are colored by the direction of the ray
+/// alone, with z+ up.
+pub trait Background {
+    /// Returns the color of this background in `direction`, which need not have a length
+    /// of 1.
+    fn color(&self, direction: Vector) -> Color;
+}
+
+impl Background for Color {
+    fn color(&self, _direction: Vector) -> Color {
+        *self
+    }
+}
+
+impl<F: Fn(Vector) -> Color> Background for F {
+    fn color(&self, direction: Vector) -> Color {
+        self(direction)
+    }
+}
+
+/// A blend from one color straight down to another straight up, such as from the
+/// horizon to the top of the sky.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Sky {
+    /// The color straight down.
+    pub below: Color,
+    /// The color straight up.
+    pub above: Color,
+    /// How the colors blend in between.
+    pub easing: Easing,
+}
+
+impl Background for Sky {
+    fn color(&self, direction: Vector) -> Color {
+        let up = direction.normalized().z();
+        self.below
+            .lerp(self.above, self.easing.ease((up + 1.0) / 2.0))
+    }
+}
+
+/// Stars of random brightness scattered over a sky.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Starfield {
+    /// The color between the stars.
+    pub sky: Color,
+    /// The fraction of the sky with a star, from 0 to 1.
+    pub density: f64,
+    /// How wide stars are, in radians.
+    pub size: f64,
+    /// The seed the stars are laid out by, each seed giving a different sky.
+    pub seed: u64,
+}
+
+impl Background for Starfield {
+    fn color(&self, direction: Vector) -> Color {
+        // The sky is divided into patches by a grid of cubes the size of a star around
+        // the camera, each of which has a star or not.
+        let point = direction.normalized() / self.size;
+        let cell = |n: f64| n.floor() as i64 as u64;
+        let mut random =
+            Random::from_values([self.seed, cell(point.x()), cell(point.y()), cell(point.z())]);
+        if random.next_f64() >= self.density {
+            return self.sky;
+        }
+        // Dim stars are more common than bright ones.
+        let brightness = random.next_f64().powi(2);
+        self.sky.lerp(Color::WHITE, 0.3 + 0.7 * brightness)
+    }
+}
+
+/// An image wrapped all the way around the scene, such as a photograph of
+/// surroundings, in the equirectangular projection of panoramas.
+///
+/// Across the image is all the way around, with straight ahead along y+ in the middle,
+/// and up the image is from straight down to straight up.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Environment {
+    /// The image and how it is sampled.
+    pub texture: ImageTexture,
+}
+
+impl Environment {
+    /// Returns an environment of `texture`.
+    pub fn new(texture: ImageTexture) -> Self {
+        Self { texture }
+    }
+}
+
+impl Background for Environment {
+    fn color(&self, direction: Vector) -> Color {
+        let direction = direction.normalized();
+        let u = 0.5 + direction.x().atan2(direction.y()) / TAU;
+        let v = 0.5 + direction.z().clamp(-1.0, 1.0).asin() / PI;
+        self.texture.sample((u, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Background, Environment, Sky, Starfield};
+    use crate::{util::check_about, Color, Easing, Image, ImageTexture, Vector};
+
+    #[test]
+    fn sky() {
+        let sky = Sky {
+            below: Color::BLACK,
+            above: Color::BLUE,
+            easing: Easing::Linear,
+        };
+        assert_eq!(sky.color(Vector(0.0, 0.0, -2.0)), Color::BLACK);
+        assert_eq!(sky.color(Vector(0.0, 0.0, 3.0)), Color::BLUE);
+        check_about(sky.color(Vector(1.0, 1.0, 0.0)).2, 0.5);
+        assert_eq!(Color::RED.color(Vector(0.0, 1.0, 0.0)), Color::RED);
+    }
+
+    #[test]
+    fn starfield() {
+        let stars = Starfield {
+            sky: Color::BLACK,
+            density: 0.1,
+            size: 0.01,
+            seed: 3,
+        };
+        let colors: Vec<_> = (0..2000)
+            .map(|i| {
+                let angle = i as f64 * 0.001;
+                stars.color(Vector(angle.sin(), angle.cos(), 0.3))
+            })
+            .collect();
+        let lit = colors
+            .iter()
+            .filter(|&&color| color != Color::BLACK)
+            .count();
+        // About a tenth of the patches the directions pass through have a star.
+        assert!((50..300).contains(&lit), "{}", lit);
+        assert!(colors.iter().all(|color| color.0 <= 1.0));
+        // The same direction always sees the same sky, and another seed another sky.
+        let direction = Vector(0.2, 1.0, 0.1);
+        assert_eq!(stars.color(direction), stars.color(direction * 2.0));
+        let other = Starfield { seed: 4, ..stars };
+        assert!((0..2000).any(|i| {
+            let direction = Vector(i as f64 * 0.001, 1.0, 0.0);
+            stars.color(direction) != other.color(direction)
+        }));
+    }
+
+    #[test]
+    fn environment() {
+        // A strip of red behind, green to the left, blue ahead and white to the right.
+        let mut image = Image::new(4, 1, Color::BLACK);
+        for (x, color) in [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE]
+            .into_iter()
+            .enumerate()
+        {
+            *image.get_mut(x, 0).unwrap() = color;
+        }
+        let mut texture = ImageTexture::new(image);
+        texture.filter = crate::Filter::Nearest;
+        let environment = Environment::new(texture);
+        assert_eq!(environment.color(Vector(0.0, -1.0, 0.1)), Color::RED);
+        assert_eq!(environment.color(Vector(-1.0, 0.0, 0.0)), Color::GREEN);
+        assert_eq!(environment.color(Vector(0.1, 1.0, 0.0)), Color::BLUE);
+        assert_eq!(environment.color(Vector(1.0, -0.1, -0.5)), Color::WHITE);
+    }
+}
@@ -0,0 +1,503 @@
+//! Parsing of command-line arguments.
+
+use std::{error::Error, fmt, path::PathBuf, str::FromStr, sync::Arc};
+
+use crate::{
+    ColorMode, Description, Framebuffer, Hittable, PathTracer, RenderMode, Sampling, Scene,
+    SceneError, Viewport,
+};
+
+/// The help text printed by `--help`.
+pub const USAGE: &str = "\
+Usage: shapes [OPTIONS]
+
+Renders simple 3D objects in the terminal, or to a file.
+
+Options:
+      --scene <NAME>        Scene to render: demo, cube, torus or flight
+                            [default: demo]
+      --scene-file <FILE>   Scene file to render instead of --scene, reloaded in the
+                            terminal whenever it changes
+      --size <WxH>          Size in terminal cells, or in pixels for images
+                            [default: 80x40, or 640x640 for images]
+      --fps <N>             Frames per second [default: as many as possible in the
+                            terminal, or 20 in files]
+      --frames <N>          Number of frames to render before exiting [default: forever
+                            in the terminal, or 100 for animations in files]
+      --duration <SECONDS>  Time into the animation to stop rendering at, counting from
+                            the first frame
+      --time <SECONDS>      Time into the animation of the first frame [default: 0]
+      --render-mode <MODE>  Characters to draw with: block, half or ascii
+                            [default: block]
+      --color <MODE>        Terminal colors: none, 16, 256 or truecolor [default: 16]
+      --samples <NxM>       Cast a grid of N by M rays through each cell and average
+                            them, smoothing edges [default: 1x1]
+      --jitter              Cast the rays of --samples through random points of their
+                            part of the cell instead of its center
+      --path-trace <N>      Path trace N samples of each cell instead, lit by the lights
+                            and glowing objects of the scene; in the terminal the image
+                            refines as the animation stands still at --time
+      --seed <N>            Seed of the random samples of --path-trace [default: 0]
+      --transparent         Leave out the background of the scene, drawing nothing
+                            where it alone is seen
+      --depth-cue <AMOUNT>  Dim what is farther away by up to AMOUNT, from 0 to 1, to
+                            make depth easier to see [default: 0]
+      --depth-range <NEAR..FAR>
+                            Distances from the camera over which --depth-cue dims from
+                            nothing to all of AMOUNT [default: as far as the scene
+                            reaches, except for the flight, which needs one]
+      --threads <N>         Number of threads to render with [default: all available]
+      --cell <WxH>          Draw images as terminal cells of this many pixels
+      --output <FILE>       Write to FILE instead of the terminal, in a format chosen by
+                            its extension: .png, .ppm, .gif, .cast (asciicast), .txt, or
+                            ANSI escape sequences otherwise
+  -i, --interactive         Orbit the view with the arrow keys or WASD, zoom with + and
+                            -, pause with space and quit with q
+  -h, --help                Print this help
+";
+
+/// Options of a run of the program.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// The scene to render.
+    pub scene: Scene,
+    /// The file describing the scene to render instead of [`scene`](Self::scene).
+    pub scene_file: Option<PathBuf>,
+    /// The width and height in terminal cells, or in pixels for images.
+    pub size: Option<(usize, usize)>,
+    /// The number of frames per second.
+    pub fps: Option<f64>,
+    /// The number of frames to render.
+    pub frames: Option<usize>,
+    /// The time from the first frame after which no more frames are rendered, in
+    /// seconds.
+    pub duration: Option<f64>,
+    /// The time into the animation of the first frame, in seconds.
+    pub time: f64,
+    /// The characters to draw cells with.
+    pub render_mode: RenderMode,
+    /// The colors to draw cells with.
+    pub color_mode: ColorMode,
+    /// Where within each cell rays are cast.
+    pub sampling: Sampling,
+    /// The number of samples of each cell to path trace the scene with, if it is
+    /// [path traced](Scene::path_traced).
+    pub path_samples: Option<usize>,
+    /// The seed that path traced samples are laid out by.
+    pub seed: u64,
+    /// Whether the background of the scene is left out.
+    pub transparent: bool,
+    /// How much the farthest parts of the scene are dimmed, from 0 to 1, by
+    /// [depth cueing](Framebuffer::depth_cue).
+    pub depth_cue: f64,
+    /// The distances from the camera over which [depth cueing](Framebuffer::depth_cue)
+    /// dims from nothing to all of it, or [those of the scene](Scene::depth_range) if
+    /// [`None`], leaving a scene without any as it is.
+    pub depth_range: Option<(f64, f64)>,
+    /// The number of threads to render with, or all available if [`None`].
+    pub threads: Option<usize>,
+    /// The width and height in pixels of a terminal cell when drawing images.
+    pub cell: Option<(usize, usize)>,
+    /// The file to write to instead of the terminal.
+    pub output: Option<PathBuf>,
+    /// Whether the view can be orbited and the animation paused with the keyboard.
+    pub interactive: bool,
+}
+
+impl Options {
+    /// Returns whether a run is over by the `frame`th frame, `time` seconds after the
+    /// first, stopping after `frames` frames if neither limit is set.
+    pub(crate) fn is_over(&self, frame: usize, time: f64, frames: Option<usize>) -> bool {
+        let frames = match self.duration {
+            None => self.frames.or(frames),
+            Some(_) => self.frames,
+        };
+        frames.is_some_and(|frames| frame >= frames)
+            || self.duration.is_some_and(|duration| time >= duration)
+    }
+
+    /// Returns these options with their scene read from their scene file, if they have
+    /// one.
+    pub(crate) fn load_scene(&self) -> Result<Self, SceneError> {
+        let mut options = self.clone();
+        if let Some(path) = options.scene_file.take() {
+            options.scene = Scene::Described(Arc::new(Description::load(&path)?));
+        }
+        Ok(options)
+    }
+
+    /// Renders the scene `time` seconds after the first frame through `viewport`, with
+    /// the sampling of these options, or path traced with all of their samples.
+    pub(crate) fn rasterize(&self, viewport: &Viewport, time: f64) -> Framebuffer {
+        let time = self.time + time;
+        let mut framebuffer = match self.path_tracer(viewport) {
+            Some(mut tracer) => {
+                let scene = self.scene.path_traced(time);
+                while self.refine(&mut tracer, &scene) {}
+                tracer.framebuffer()
+            }
+            None => self.rasterize_scene(viewport, self.scene.at(time)),
+        };
+        self.depth_cue(&mut framebuffer, self.scene.depth_range());
+        framebuffer
+    }
+
+    /// Returns a path tracer of `viewport` with the seed of these options, if they path
+    /// trace the scene.
+    pub(crate) fn path_tracer(&self, viewport: &Viewport) -> Option<PathTracer> {
+        let viewport = Viewport {
+            background: !self.transparent,
+            ..*viewport
+        };
+        self.path_samples
+            .map(|_| PathTracer::new(viewport, self.seed))
+    }
+
+    /// Adds a sample of `scene` to `tracer` on the threads of these options, unless it
+    /// has taken all of their samples already, returning whether it took one.
+    pub(crate) fn refine(&self, tracer: &mut PathTracer, scene: impl Hittable + Sync) -> bool {
+        if tracer.samples() >= self.path_samples.unwrap_or(0) {
+            return false;
+        }
+        match self.threads {
+            Some(threads) => tracer.sample_with_threads(scene, threads),
+            None => tracer.sample(scene),
+        }
+        true
+    }
+
+    /// Depth cues `framebuffer` as these options say, over the distances `range` of the
+    /// scene unless they give their own.
+    pub(crate) fn depth_cue(&self, framebuffer: &mut Framebuffer, range: Option<(f64, f64)>) {
+        if self.depth_cue > 0.0 {
+            if let Some((near, far)) = self.depth_range.or(range) {
+                framebuffer.depth_cue(near, far, self.depth_cue);
+            }
+        }
+    }
+
+    /// Renders `scene` through `viewport` with the sampling and threads of these
+    /// options.
+    pub(crate) fn rasterize_scene(
+        &self,
+        viewport: &Viewport,
+        scene: impl Hittable + Sync,
+    ) -> Framebuffer {
+        let viewport = Viewport {
+            sampling: self.sampling,
+            background: !self.transparent,
+            ..*viewport
+        };
+        match self.threads {
+            Some(threads) => viewport.rasterize_with_threads(scene, threads),
+            None => viewport.rasterize(scene),
+        }
+    }
+}
+
+/// What the command-line arguments ask for.
+#[derive(Clone, Debug)]
+pub enum Command {
+    /// Print [`USAGE`].
+    Help,
+    /// Run with the given options.
+    Run(Box<Options>),
+}
+
+/// An invalid command-line argument.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ArgsError(String);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for ArgsError {}
+
+/// A width and height written as `WxH`.
+struct Size(usize, usize);
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("expected a size like 80x40, not `{}`", s);
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+        match (width.parse(), height.parse()) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok(Self(width, height)),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// A range of distances written as `NEAR..FAR`.
+struct DepthRange(f64, f64);
+
+impl FromStr for DepthRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("expected a range of distances like 2..5, not `{}`", s);
+        let (near, far) = s.split_once("..").ok_or_else(error)?;
+        match (near.parse::<f64>(), far.parse::<f64>()) {
+            (Ok(near), Ok(far)) if 0.0 <= near && near < far && far.is_finite() => {
+                Ok(Self(near, far))
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Parses the value of `flag`, which must satisfy `valid`.
+fn parse_value<T: FromStr>(
+    flag: &str,
+    value: &str,
+    valid: impl FnOnce(&T) -> bool,
+) -> Result<T, ArgsError>
+where
+    T::Err: fmt::Display,
+{
+    match value.parse() {
+        Ok(parsed) if valid(&parsed) => Ok(parsed),
+        Ok(_) => Err(ArgsError(format!("invalid value `{}` for {}", value, flag))),
+        Err(error) => Err(ArgsError(format!("invalid value for {}: {}", flag, error))),
+    }
+}
+
+/// Parses command-line arguments, not including the name of the program.
+///
+/// Values can follow their flag either as the next argument or after an `=`.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, ArgsError> {
+    let mut options = Options::default();
+    let (mut samples, mut jitter) = (None, false);
+    // Whether `--scene` was given, which a scene file would silently replace.
+    let mut scene = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, mut inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        if matches!(flag, "-h" | "--help") && inline.is_none() {
+            return Ok(Command::Help);
+        }
+        let mut value = || {
+            inline
+                .take()
+                .or_else(|| args.next())
+                .ok_or_else(|| ArgsError(format!("{} requires a value", flag)))
+        };
+
+        match flag {
+            "--scene" => {
+                options.scene = parse_value(flag, &value()?, |_| true)?;
+                scene = true;
+            }
+            "--scene-file" => options.scene_file = Some(value()?.into()),
+            "--size" => {
+                let Size(width, height) = parse_value(flag, &value()?, |_| true)?;
+                options.size = Some((width, height));
+            }
+            "--fps" => {
+                options.fps = Some(parse_value(flag, &value()?, |fps: &f64| {
+                    fps.is_finite() && *fps > 0.0
+                })?)
+            }
+            "--frames" => options.frames = Some(parse_value(flag, &value()?, |n| *n > 0)?),
+            "--duration" => {
+                options.duration = Some(parse_value(flag, &value()?, |duration: &f64| {
+                    duration.is_finite() && *duration > 0.0
+                })?)
+            }
+            "--time" => options.time = parse_value(flag, &value()?, |time: &f64| time.is_finite())?,
+            "--render-mode" => options.render_mode = parse_value(flag, &value()?, |_| true)?,
+            "--color" => options.color_mode = parse_value(flag, &value()?, |_| true)?,
+            "--samples" => {
+                let Size(columns, rows) = parse_value(flag, &value()?, |Size(columns, rows)| {
+                    columns.checked_mul(*rows).is_some()
+                })?;
+                samples = Some((columns, rows));
+            }
+            "--jitter" => jitter = true,
+            "--path-trace" => {
+                options.path_samples = Some(parse_value(flag, &value()?, |n| *n > 0)?)
+            }
+            "--seed" => options.seed = parse_value(flag, &value()?, |_| true)?,
+            "--transparent" => options.transparent = true,
+            "--depth-cue" => {
+                options.depth_cue =
+                    parse_value(flag, &value()?, |amount: &f64| (0.0..=1.0).contains(amount))?
+            }
+            "--depth-range" => {
+                let DepthRange(near, far) = parse_value(flag, &value()?, |_| true)?;
+                options.depth_range = Some((near, far));
+            }
+            "--threads" => options.threads = Some(parse_value(flag, &value()?, |n| *n > 0)?),
+            "--cell" => {
+                let Size(width, height) = parse_value(flag, &value()?, |_| true)?;
+                options.cell = Some((width, height));
+            }
+            "--output" => options.output = Some(value()?.into()),
+            "-i" | "--interactive" => options.interactive = true,
+            _ => return Err(ArgsError(format!("unexpected argument `{}`", arg))),
+        }
+
+        if inline.is_some() {
+            return Err(ArgsError(format!("{} does not take a value", flag)));
+        }
+    }
+    if scene && options.scene_file.is_some() {
+        return Err(ArgsError(
+            "--scene cannot be used with --scene-file".to_owned(),
+        ));
+    }
+    let flies_through = options.scene_file.is_none() && options.scene.depth_range().is_none();
+    if options.depth_cue > 0.0 && options.depth_range.is_none() && flies_through {
+        return Err(ArgsError(
+            "--depth-cue needs --depth-range for the flight".to_owned(),
+        ));
+    }
+    if options.interactive && options.output.is_some() {
+        return Err(ArgsError(
+            "--interactive cannot be used with --output".to_owned(),
+        ));
+    }
+    let (columns, rows) = samples.unwrap_or((1, 1));
+    options.sampling = match (columns * rows, jitter) {
+        (1, false) => Sampling::Center,
+        (_, false) => Sampling::Grid { columns, rows },
+        (_, true) => Sampling::Jittered { columns, rows },
+    };
+    Ok(Command::Run(Box::new(options)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, ArgsError, Command, Options};
+    use crate::{ColorMode, RenderMode, Sampling, Scene};
+
+    fn parse_options(args: &[&str]) -> Result<Options, ArgsError> {
+        match parse(args.iter().map(|&arg| arg.to_owned()))? {
+            Command::Run(options) => Ok(*options),
+            Command::Help => panic!("unexpected help"),
+        }
+    }
+
+    #[test]
+    fn values() {
+        let options = parse_options(&[
+            "--scene",
+            "torus",
+            "--size=120x50",
+            "--fps",
+            "12.5",
+            "--frames=3",
+            "--duration",
+            "2",
+            "--render-mode",
+            "half",
+            "--color",
+            "truecolor",
+            "--threads",
+            "2",
+            "--samples=3x2",
+            "--jitter",
+            "--path-trace=64",
+            "--seed",
+            "12",
+            "--transparent",
+            "--depth-cue=0.6",
+            "--depth-range",
+            "1.5..4",
+            "--output",
+            "torus.gif",
+        ])
+        .unwrap();
+        assert_eq!(options.scene, Scene::Torus);
+        assert_eq!(options.size, Some((120, 50)));
+        assert_eq!(options.fps, Some(12.5));
+        assert_eq!(options.frames, Some(3));
+        assert_eq!(options.duration, Some(2.0));
+        assert_eq!(options.render_mode, RenderMode::HalfBlock);
+        assert_eq!(options.color_mode, ColorMode::TrueColor);
+        assert_eq!(options.threads, Some(2));
+        assert_eq!(
+            options.sampling,
+            Sampling::Jittered {
+                columns: 3,
+                rows: 2
+            }
+        );
+        assert_eq!(options.path_samples, Some(64));
+        assert_eq!(options.seed, 12);
+        assert!(options.transparent);
+        assert_eq!(options.depth_cue, 0.6);
+        assert_eq!(options.depth_range, Some((1.5, 4.0)));
+        assert_eq!(options.output.unwrap().to_str(), Some("torus.gif"));
+    }
+
+    #[test]
+    fn limits() {
+        let options = parse_options(&["--duration", "1.5"]).unwrap();
+        assert!(!options.is_over(40, 1.4, Some(10)));
+        assert!(options.is_over(0, 1.5, None));
+        let options = parse_options(&["--frames", "3"]).unwrap();
+        assert!(!options.is_over(2, 100.0, Some(1)));
+        assert!(options.is_over(3, 0.0, None));
+        let options = Options::default();
+        assert!(!options.is_over(1000, 1000.0, None));
+        assert!(options.is_over(10, 0.0, Some(10)));
+    }
+
+    #[test]
+    fn help() {
+        assert!(matches!(
+            parse(["--frames=2", "-h"].map(String::from)),
+            Ok(Command::Help)
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        for args in [
+            &["--scene", "sphere"][..],
+            &["--size", "80"],
+            &["--size", "0x40"],
+            &["--fps", "-1"],
+            &["--frames", "0"],
+            &["--frames"],
+            &["--threads", "0"],
+            &["--depth-cue", "1.5"],
+            &["--depth-range", "4..2"],
+            &["--depth-range", "2"],
+            &["--samples", "0x2"],
+            &["--samples", "100000x1000000000000000"],
+            &["--jitter=yes"],
+            &["--path-trace", "0"],
+            &["--seed", "-1"],
+            &["--transparent=no"],
+            &["--interactive", "--output", "demo.gif"],
+            &["--duration", "0"],
+            &["--help=yes"],
+            &["--colour", "256"],
+            &["extra"],
+        ] {
+            assert!(parse_options(args).is_err(), "{:?} should not parse", args);
+        }
+        assert_eq!(
+            parse_options(&["--scene-file", "a.scene", "--scene", "torus"]).unwrap_err(),
+            ArgsError("--scene cannot be used with --scene-file".to_owned())
+        );
+        assert!(parse_options(&["--scene-file", "a.scene"]).is_ok());
+        // The camera flies through the flight, so there is no range to assume.
+        assert_eq!(
+            parse_options(&["--scene", "flight", "--depth-cue", "0.5"]).unwrap_err(),
+            ArgsError("--depth-cue needs --depth-range for the flight".to_owned())
+        );
+        assert!(
+            parse_options(&["--scene=flight", "--depth-cue=0.5", "--depth-range=1..9"]).is_ok()
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Tells the time of each frame of an animation, in seconds from its first frame.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Clock {
+    /// Each frame is at the time it is rendered.
+    RealTime { start: Instant },
+    /// Frames are `1 / fps` seconds apart, and each is waited for when `paced`.
+    Fixed {
+        start: Instant,
+        fps: f64,
+        paced: bool,
+    },
+}
+
+impl Clock {
+    /// Returns a clock at which frames are shown as soon as they are rendered.
+    pub(crate) fn real_time() -> Self {
+        Self::RealTime {
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns a clock of `fps` frames per second that never waits, for rendering
+    /// frames ahead of time.
+    pub(crate) fn fixed(fps: f64) -> Self {
+        Self::Fixed {
+            start: Instant::now(),
+            fps,
+            paced: false,
+        }
+    }
+
+    /// Returns a clock of `fps` frames per second that waits for the time of each frame.
+    pub(crate) fn paced(fps: f64) -> Self {
+        Self::Fixed {
+            start: Instant::now(),
+            fps,
+            paced: true,
+        }
+    }
+
+    /// Returns the time of the `frame`th frame, waiting until then if this clock is paced.
+    pub(crate) fn frame_time(&self, frame: usize) -> f64 {
+        match *self {
+            Self::RealTime { start } => start.elapsed().as_secs_f64(),
+            Self::Fixed { start, fps, paced } => {
+                let time = frame as f64 / fps;
+                if paced {
+                    let elapsed = start.elapsed().as_secs_f64();
+                    if time > elapsed {
+                        thread::sleep(Duration::from_secs_f64(time - elapsed));
+                    }
+                }
+                time
+            }
+        }
+    }
+}
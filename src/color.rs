@@ -1,23 +1,109 @@
-use std::fmt;
-
-/// Colors that can be displayed as ANSI escape sequences.
-#[derive(Copy, Clone, Debug)]
-#[repr(u8)]
-pub(crate) enum Color {
-    #[allow(dead_code)]
-    Black = 30,
-    Red = 31,
-    Green = 32,
-    Yellow = 33,
-    Blue = 34,
-    Magenta = 35,
-    Cyan = 36,
-    White = 37,
-    Reset = 0,
-}
-
-impl fmt::Display for Color {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "\x1b[{}m", *self as u8)
+use std::ops;
+
+/// A color composed of red, green and blue components, each nominally between 0 and 1.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Color(
+    /// The red component of this color.
+    pub f64,
+    /// The green component of this color.
+    pub f64,
+    /// The blue component of this color.
+    pub f64,
+);
+
+impl Color {
+    pub const BLACK: Self = Self(0.0, 0.0, 0.0);
+    pub const RED: Self = Self(1.0, 0.0, 0.0);
+    pub const GREEN: Self = Self(0.0, 1.0, 0.0);
+    pub const YELLOW: Self = Self(1.0, 1.0, 0.0);
+    pub const BLUE: Self = Self(0.0, 0.0, 1.0);
+    pub const MAGENTA: Self = Self(1.0, 0.0, 1.0);
+    pub const CYAN: Self = Self(0.0, 1.0, 1.0);
+    pub const WHITE: Self = Self(1.0, 1.0, 1.0);
+
+    /// Returns this color as 8-bit red, green and blue components, clamping
+    /// out-of-range values.
+    pub fn to_rgb8(self) -> [u8; 3] {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [channel(self.0), channel(self.1), channel(self.2)]
+    }
+
+    /// Returns the relative luminance of this color.
+    pub fn luminance(self) -> f64 {
+        0.2126 * self.0 + 0.7152 * self.1 + 0.0722 * self.2
+    }
+
+    /// Returns the SGR code of the closest of the eight basic ANSI foreground colors,
+    /// from 30 (black) to 37 (white).
+    pub fn ansi(self) -> u8 {
+        let bit = |c: f64, shift: u8| u8::from(c >= 0.5) << shift;
+        30 + (bit(self.0, 0) | bit(self.1, 1) | bit(self.2, 2))
+    }
+}
+
+impl ops::Add for Color {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl ops::AddAssign for Color {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Mul for Color {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0, self.1 * rhs.1, self.2 * rhs.2)
+    }
+}
+
+impl ops::Mul<f64> for Color {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
+impl ops::Mul<Color> for f64 {
+    type Output = Color;
+
+    fn mul(self, rhs: Color) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl ops::Div<f64> for Color {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self(self.0 / rhs, self.1 / rhs, self.2 / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    #[test]
+    fn ansi() {
+        assert_eq!(Color::BLACK.ansi(), 30);
+        assert_eq!(Color::RED.ansi(), 31);
+        assert_eq!(Color::YELLOW.ansi(), 33);
+        assert_eq!(Color::CYAN.ansi(), 36);
+        assert_eq!(Color::WHITE.ansi(), 37);
+        assert_eq!(Color(0.9, 0.2, 0.8).ansi(), 35);
+    }
+
+    #[test]
+    fn to_rgb8() {
+        assert_eq!(Color(0.0, 0.5, 1.0).to_rgb8(), [0, 128, 255]);
+        assert_eq!(Color(-1.0, 2.0, 0.2).to_rgb8(), [0, 255, 51]);
     }
 }
@@ -3,7 +3,7 @@ use std::f64::consts::FRAC_PI_2;
 use crate::{Color, Hit, Hittable, HittableExt, Matrix, Ray, Translation, Vector};
 
 /// Returns a cube, centered at the origin, with a side length of 1.
-pub(crate) fn cube() -> impl Hittable + Copy {
+pub fn cube() -> impl Hittable + Copy {
     // A side of the cube. This is a square in the xz plane, centered at the origin and
     // with a side length of 1.
     #[derive(Copy, Clone)]
@@ -14,11 +14,16 @@ pub(crate) fn cube() -> impl Hittable + Copy {
             // First, determine the point at y = 0.
             let t = -incidence.origin.y() / incidence.direction.y();
             let point = incidence.at(t);
-            // Check if the point is inside the boundaries
-            if -0.5 <= point.x() && point.x() <= 0.5 && -0.5 <= point.z() && point.z() <= 0.5 {
+            // Check if the point is in front of the ray and inside the boundaries
+            if t >= 0.0
+                && -0.5 <= point.x()
+                && point.x() <= 0.5
+                && -0.5 <= point.z()
+                && point.z() <= 0.5
+            {
                 Some(Hit {
-                    color: Color::Red,
-                    t,
+                    uv: (point.x() + 0.5, point.z() + 0.5),
+                    ..Hit::new(Color::RED, t)
                 })
             } else {
                 None
@@ -30,24 +35,50 @@ pub(crate) fn cube() -> impl Hittable + Copy {
     let y1 = Side.transform(Translation(Vector(0.0, -0.5, 0.0)));
     let y2 = Side
         .transform(Translation(Vector(0.0, 0.5, 0.0)))
-        .colorize(Color::Yellow);
+        .colorize(Color::YELLOW);
 
     // Rotate in the x-axis for xy sides.
     let z1 = y1
         .transform(Matrix::rotation_x(FRAC_PI_2))
-        .colorize(Color::Blue);
+        .colorize(Color::BLUE);
     let z2 = y1
         .transform(Matrix::rotation_x(-FRAC_PI_2))
-        .colorize(Color::Green);
+        .colorize(Color::GREEN);
 
     // Rotate in the z-axis for yz sides.
     let x1 = y1
         .transform(Matrix::rotation_z(FRAC_PI_2))
-        .colorize(Color::Cyan);
+        .colorize(Color::CYAN);
     let x2 = y1
         .transform(Matrix::rotation_z(-FRAC_PI_2))
-        .colorize(Color::Magenta);
+        .colorize(Color::MAGENTA);
+
+    // The sides of the cube, which are hit at points in the space of the whole cube
+    // rather than of the side that was hit, facing out of the cube.
+    #[derive(Copy, Clone)]
+    struct Cube<H>(H);
+
+    impl<H: Hittable> Hittable for Cube<H> {
+        fn hit(&self, incidence: Ray) -> Option<Hit> {
+            self.0.hit(incidence).map(|hit| {
+                let point = incidence.at(hit.t);
+                let Vector(x, y, z) = point;
+                let normal = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+                    Vector(x.signum(), 0.0, 0.0)
+                } else if y.abs() >= z.abs() {
+                    Vector(0.0, y.signum(), 0.0)
+                } else {
+                    Vector(0.0, 0.0, z.signum())
+                };
+                Hit {
+                    point,
+                    normal,
+                    ..hit
+                }
+            })
+        }
+    }
 
     // Combine all of the sides together.
-    y1.and(y2).and(z1).and(z2).and(x1).and(x2)
+    Cube(y1.and(y2).and(z1).and(z2).and(x1).and(x2))
 }
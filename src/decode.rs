@@ -0,0 +1,3 @@
+//! Decoders that turn bytes into [`Image`](crate::Image)s.
+
+pub mod ppm;
@@ -0,0 +1,181 @@
+use std::io;
+
+use crate::{Color, Image};
+
+/// The most pixels an image can have, which keeps a corrupt header from asking for more
+/// memory than there is.
+const MAX_PIXELS: usize = 1 << 26;
+
+/// Returns an error saying that the data is not a valid image because of `message`.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+/// The bytes of a netpbm file, read from the start.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    /// Skips whitespace and comments, which run from `#` to the end of the line.
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b'#' => {
+                    while self
+                        .bytes
+                        .get(self.position)
+                        .is_some_and(|&byte| byte != b'\n')
+                    {
+                        self.position += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Returns the next number written in decimal, or an error saying that `what` is
+    /// missing.
+    fn number(&mut self, what: &str) -> io::Result<u32> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(u8::is_ascii_digit)
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| invalid(&format!("expected {}", what)))
+    }
+
+    /// Returns the next sample stored in binary, in one byte or two if `wide`.
+    fn binary(&mut self, wide: bool) -> io::Result<u32> {
+        let size = if wide { 2 } else { 1 };
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + size)
+            .ok_or_else(|| invalid("the pixel data ends early"))?;
+        self.position += size;
+        Ok(bytes
+            .iter()
+            .fold(0, |sample, &byte| sample << 8 | byte as u32))
+    }
+}
+
+/// Reads a PPM (`P3` or `P6`) or PGM (`P2` or `P5`) image, in plain text or binary, with
+/// up to 16 bits per channel.
+pub fn decode(mut buf: impl io::Read) -> io::Result<Image> {
+    let mut bytes = Vec::new();
+    buf.read_to_end(&mut bytes)?;
+    let (channels, plain) = match bytes.get(..2) {
+        Some(b"P2") => (1, true),
+        Some(b"P3") => (3, true),
+        Some(b"P5") => (1, false),
+        Some(b"P6") => (3, false),
+        _ => return Err(invalid("not a PPM or PGM image")),
+    };
+    let mut reader = Reader {
+        bytes: &bytes,
+        position: 2,
+    };
+    let width = reader.number("a width")? as usize;
+    let height = reader.number("a height")? as usize;
+    let max = match reader.number("a maximum value")? {
+        max @ 1..=65535 => max,
+        _ => return Err(invalid("the maximum value must be from 1 to 65535")),
+    };
+    // A single whitespace character separates the header from binary data.
+    reader.position += 1;
+
+    // The size is checked against the data before anything is allocated for it, as
+    // plain samples take at least a digit and a space and binary ones a byte or two.
+    let samples = match width.checked_mul(height) {
+        Some(pixels) if pixels <= MAX_PIXELS => pixels * channels,
+        _ => return Err(invalid("the image is too large")),
+    };
+    let needed = match plain {
+        true => (2 * samples).saturating_sub(1),
+        false if max > 255 => 2 * samples,
+        false => samples,
+    };
+    if bytes.len().saturating_sub(reader.position) < needed {
+        return Err(invalid("the pixel data ends early"));
+    }
+
+    let mut image = Image::new(width, height, Color::BLACK);
+    let mut sample = || -> io::Result<f64> {
+        let value = match plain {
+            true => reader.number("a sample")?,
+            false => reader.binary(max > 255)?,
+        };
+        Ok(value.min(max) as f64 / max as f64)
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let color = match channels {
+                1 => {
+                    let gray = sample()?;
+                    Color(gray, gray, gray)
+                }
+                _ => Color(sample()?, sample()?, sample()?),
+            };
+            *image.get_mut(x, y).unwrap() = color;
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use crate::{encode::ppm, Color, Image};
+
+    #[test]
+    fn binary() {
+        let mut image = Image::new(3, 2, Color::RED);
+        *image.get_mut(2, 1).unwrap() = Color(0.0, 0.2, 1.0);
+        let mut bytes = Vec::new();
+        ppm::encode(&mut bytes, &image).unwrap();
+        assert_eq!(decode(&bytes[..]).unwrap(), image);
+
+        let gray = decode(&b"P5 2 1 65535\n\x80\x00\xff\xff"[..]).unwrap();
+        assert!((gray.get(0, 0).unwrap().0 - 32768.0 / 65535.0).abs() < 1e-9);
+        assert_eq!(gray.get(1, 0), Some(&Color::WHITE));
+    }
+
+    #[test]
+    fn plain() {
+        let image = decode(&b"P3\n# a comment\n2 1 # another\n4\n4 0 0  0 2 4\n"[..]).unwrap();
+        assert_eq!(image.get(0, 0), Some(&Color::RED));
+        assert_eq!(image.get(1, 0), Some(&Color(0.0, 0.5, 1.0)));
+        let image = decode(&b"P2 1 2 10 0 10"[..]).unwrap();
+        assert_eq!(image.get(0, 1), Some(&Color::WHITE));
+    }
+
+    #[test]
+    fn errors() {
+        for (bytes, error) in [
+            (&b"GIF89a"[..], "not a PPM or PGM image"),
+            (b"P6 2", "expected a height"),
+            (b"P6 2 2 0\n", "the maximum value must be from 1 to 65535"),
+            (b"P6 1 1 255\n\0\0", "the pixel data ends early"),
+            (b"P3 1 1 255 0 0 x", "expected a sample"),
+            (b"P3 2 1 255 0 0 0 0 0", "the pixel data ends early"),
+            (b"P5 2 2 65535\n\0\0\0\0", "the pixel data ends early"),
+            (
+                b"P6 4000000000 4000000000 255\n\0\0",
+                "the image is too large",
+            ),
+            (b"P2 100000 100000 255 0", "the image is too large"),
+        ] {
+            assert_eq!(decode(bytes).unwrap_err().to_string(), error);
+        }
+    }
+}
@@ -0,0 +1,1323 @@
+//! Scenes described by text, such as scene files.
+
+use std::{
+    f64::consts::TAU,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    cube, decode::ppm, scene, Animate, Checker, Color, Easing, EasingError, Environment, Falloff,
+    Filter, Fog, Gradient, Hittable, HittableExt, ImageTexture, Keyframe, Light, Lighting, Marble,
+    Material, Matrix, Noise, Orbit, Repeat, SceneError, Sky, Starfield, Stripes, Torus, Track,
+    Translation, Vector, Wood, Wrap,
+};
+
+/// An axis of rotation.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// Returns a matrix that rotates by `angle` radians around this axis.
+    fn rotation(self, angle: f64) -> Matrix {
+        match self {
+            Self::X => Matrix::rotation_x(angle),
+            Self::Y => Matrix::rotation_y(angle),
+            Self::Z => Matrix::rotation_z(angle),
+        }
+    }
+}
+
+/// A move of an object into its place in a scene.
+#[derive(Clone, PartialEq, Debug)]
+enum Move {
+    Translate(Track<Vector>),
+    /// A rotation by an angle in radians.
+    Rotate(Axis, Track<f64>),
+    /// A full turn every period in seconds.
+    Spin(Axis, f64),
+    Scale(Track<Vector>),
+}
+
+/// The property of an object that keyframes are added to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Keyed {
+    Color,
+    /// The last move.
+    Move,
+}
+
+/// The texture of an object.
+#[derive(Clone, PartialEq, Debug)]
+enum Pattern {
+    Checker(Checker),
+    Stripes(Stripes),
+    Gradient(Gradient),
+    Noise(Noise),
+    Marble(Marble),
+    Wood(Wood),
+    Image(ImageTexture),
+}
+
+/// What is seen behind the objects of a description.
+#[derive(Clone, PartialEq, Debug)]
+enum Backdrop {
+    Color(Color),
+    Sky(Sky),
+    Stars(Starfield),
+    Environment(Environment),
+}
+
+/// The width of the stars of a starry background, in radians.
+const STAR_SIZE: f64 = 0.005;
+
+/// The shape of an object.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Shape {
+    Cube,
+    Torus(Torus),
+}
+
+/// An object of a scene.
+#[derive(Clone, PartialEq, Debug)]
+struct Object {
+    shape: Shape,
+    name: Option<String>,
+    color: Option<Track<Color>>,
+    texture: Option<Pattern>,
+    material: Material,
+    moves: Vec<Move>,
+    keyed: Option<Keyed>,
+}
+
+impl Object {
+    /// Returns an object of `shape` with no properties.
+    fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            name: None,
+            color: None,
+            texture: None,
+            material: Material::MATTE,
+            moves: Vec::new(),
+            keyed: None,
+        }
+    }
+
+    /// Returns how the keyframes of the property that is being keyed repeat.
+    fn repeat(&mut self) -> Option<&mut Repeat> {
+        match (self.keyed?, &mut self.color, self.moves.last_mut()) {
+            (Keyed::Color, Some(track), _) => Some(&mut track.repeat),
+            (Keyed::Move, _, Some(Move::Translate(track) | Move::Scale(track))) => {
+                Some(&mut track.repeat)
+            }
+            (Keyed::Move, _, Some(Move::Rotate(_, track))) => Some(&mut track.repeat),
+            _ => None,
+        }
+    }
+}
+
+/// A scene described by text, such as a scene file.
+///
+/// A description is a list of lines, each a keyword followed by its values, with
+/// anything after a `#` ignored:
+///
+/// ```text
+/// # Where the camera orbits the origin from, in degrees.
+/// camera yaw 0 pitch 0 distance 3
+///
+/// # Each object starts with its shape, followed by its properties.
+/// cube
+///     name box
+///     color cyan
+///     # Moves are applied in order, rotations in degrees.
+///     scale 1.3
+///     spin z 2.5
+///     rotate x 40
+///
+/// torus 1.5 0.3
+///     color 1 0.5 0
+///     spin x 6
+///     translate 0 0 0.2
+/// ```
+///
+/// `spin` turns an object all the way around an axis every so many seconds.
+///
+/// Scenes with lights are [lit](HittableExt::lit), shading each object by its
+/// `material`: `matte`, `glossy`, `mirror` or `glass`, optionally followed by settings
+/// of how much it is `diffuse`, `specular` and with what `shininess`, how much it
+/// should `reflect` or is `clear` (together at most 1), how much it should `refract`,
+/// and the color it should `emit`. A `light` is a `point` at a position or a `sun`
+/// shining in a direction, white unless it is given a color, and `ambient` sets the
+/// light that reaches everywhere:
+///
+/// ```text
+/// light point 2 -3 4
+/// light sun 0 1 -1 0.3 0.3 0.4
+/// ambient 0.1 0.1 0.1
+///
+/// torus 1.5 0.3
+///     material glass refract 1.3
+/// cube
+///     material glossy shininess 16
+/// ```
+///
+/// A `background` is seen wherever nothing else is, and reflected by objects: a color,
+/// a `sky` blending from a color straight down to another straight up, eased by an
+/// optional [`Easing`], `stars` over a color, with a density of how much of the sky
+/// they cover and an optional seed, or an `image` wrapped all the way around the scene:
+///
+/// ```text
+/// background sky 0.8 0.7 0.6 0.2 0.4 0.9 sin-out
+/// # Or instead:
+/// background stars black 0.02 7
+/// ```
+///
+/// `fog` blends objects into a color, black unless one is given, the farther they are
+/// from the camera: `linear` fog starts at a distance and hides everything by another,
+/// and `exponential` fog hides so much more with every unit of distance:
+///
+/// ```text
+/// fog linear 2 6
+/// # Or instead:
+/// fog exponential 0.3 0.7 0.7 0.8
+/// ```
+///
+/// When the scene is [path traced](HittableExt::path_traced), objects that `emit`
+/// light are area lights and the background lights the scene like a sky, and scenes
+/// with none of lights, such objects or a background are lit by a white sky.
+///
+/// A `texture` patterns an object in place of its color, with a `checker` of so many
+/// squares across and around it, so many pairs of `stripes`, a `gradient` eased by an
+/// optional [`Easing`], `noise`, `marble` or `wood` with features of a size and an
+/// optional seed, or an `image` from a PPM or PGM file, named relative to the scene
+/// file, and then optionally filtered by the `nearest` pixel or `bilinear` and set to
+/// `repeat` or `clamp` outside of it:
+///
+/// ```text
+/// cube
+///     texture checker white black 4 4
+/// torus 1.5 0.3
+///     texture marble white 0.2 0.2 0.3 0.5 7
+/// cube
+///     texture image logo.ppm nearest clamp
+/// ```
+///
+/// The `color`, `translate`, `rotate` and `scale` properties can be animated by
+/// keyframes on the lines after them, with the property itself as the keyframe at time
+/// 0. Each `key` gives a time in seconds, the value reached by then and, optionally, the
+/// name of the [`Easing`] from the keyframe before. A `repeat` line then says whether the
+/// keyframes `hold`, `loop` or `ping-pong` once they are over:
+///
+/// ```text
+/// cube
+///     color red
+///         key 1 blue bounce-out
+///         key 2 red cubic-bezier(0.25,0.1,0.25,1)
+///         repeat loop
+///     rotate z 0
+///         key 2.5 360 sin-in-out
+///         repeat loop
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Description {
+    camera: Orbit,
+    lighting: Lighting,
+    background: Option<Backdrop>,
+    fog: Option<Fog>,
+    objects: Vec<Object>,
+    files: Vec<PathBuf>,
+}
+
+impl Description {
+    /// Reads and parses the description in the file at `path`.
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| SceneError(format!("could not read {}: {}", path.display(), error)))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&text, base)
+            .map_err(|error| SceneError(format!("{}: {}", path.display(), error)))
+    }
+
+    /// Parses the description in `text`, with files that it names relative to `base`.
+    fn parse(text: &str, base: &Path) -> Result<Self, SceneError> {
+        let mut description = Self::default();
+        let mut files = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            if let Some(keyword) = words.next() {
+                let values = Values {
+                    words,
+                    base,
+                    files: &mut files,
+                };
+                parse_line(&mut description, keyword, values)
+                    .map_err(|error| SceneError(format!("line {}: {}", number + 1, error)))?;
+            }
+        }
+        description.files = files;
+        Ok(description)
+    }
+
+    /// Returns the files other than the description itself that were read for it, such
+    /// as the images of textures.
+    pub(crate) fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Returns the point that the camera orbits, in front of it.
+    pub fn center(&self) -> Vector {
+        Vector(0.0, self.camera.distance, 0.0)
+    }
+
+    /// Returns the names that objects of the scene are [tagged](HittableExt::named) with.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.objects
+            .iter()
+            .filter_map(|object| object.name.as_deref())
+    }
+
+    /// Returns the described scene as it appears `time` seconds into its animation.
+    pub fn at(&self, time: f64) -> impl Hittable + Sync {
+        let scene = self.surroundings(time);
+        let scene: Box<dyn Hittable + Sync> = match self.lighting.lights.is_empty() {
+            true => scene,
+            false => Box::new(scene.lit(self.lighting.clone())),
+        };
+        self.seen(scene)
+    }
+
+    /// Returns the scene described, as it appears `time` seconds into its animation,
+    /// [path traced](HittableExt::path_traced) by its lights, its background and the
+    /// objects that give off light.
+    pub fn path_traced(&self, time: f64) -> impl Hittable + Sync {
+        let glowing = self
+            .objects
+            .iter()
+            .any(|object| object.material.emission != Color::BLACK);
+        let lit = glowing || !self.lighting.lights.is_empty() || self.background.is_some();
+        let lighting = match lit {
+            true => self.lighting.clone(),
+            false => scene::daylight(),
+        };
+        self.seen(self.surroundings(time).path_traced(lighting))
+    }
+
+    /// Returns `scene` as it is seen through the camera and any fog.
+    fn seen(&self, scene: impl Hittable + Sync + 'static) -> Box<dyn Hittable + Sync> {
+        let scene = self.camera.view(scene);
+        match self.fog {
+            Some(fog) => Box::new(scene.fog(fog)),
+            None => Box::new(scene),
+        }
+    }
+
+    /// Returns the objects described in front of the background, as they appear `time`
+    /// seconds into the animation and before they are lit or seen through the camera.
+    fn surroundings(&self, time: f64) -> Box<dyn Hittable + Sync> {
+        let objects = self.objects(time);
+        match &self.background {
+            Some(Backdrop::Color(color)) => Box::new(objects.background(*color)),
+            Some(Backdrop::Sky(sky)) => Box::new(objects.background(*sky)),
+            Some(Backdrop::Stars(stars)) => Box::new(objects.background(*stars)),
+            Some(Backdrop::Environment(environment)) => {
+                Box::new(objects.background(environment.clone()))
+            }
+            None => Box::new(objects),
+        }
+    }
+
+    /// Returns the objects described, as they appear `time` seconds into the animation
+    /// and before they are lit or seen through the camera.
+    fn objects(&self, time: f64) -> Vec<Box<dyn Hittable + Sync>> {
+        self.objects
+            .iter()
+            .map(|object| {
+                let mut hittable: Box<dyn Hittable + Sync> = match object.shape {
+                    Shape::Cube => Box::new(cube()),
+                    Shape::Torus(torus) => Box::new(torus),
+                };
+                if let Some(name) = &object.name {
+                    hittable = Box::new(hittable.named(name));
+                }
+                if let Some(color) = &object.color {
+                    hittable = Box::new(hittable.colorize(color.sample(time)));
+                }
+                if object.material != Material::MATTE {
+                    hittable = Box::new(hittable.material(object.material));
+                }
+                hittable = match &object.texture {
+                    Some(Pattern::Checker(checker)) => Box::new(hittable.texture(*checker)),
+                    Some(Pattern::Stripes(stripes)) => Box::new(hittable.texture(*stripes)),
+                    Some(Pattern::Gradient(gradient)) => Box::new(hittable.texture(*gradient)),
+                    Some(Pattern::Noise(noise)) => Box::new(hittable.texture(*noise)),
+                    Some(Pattern::Marble(marble)) => Box::new(hittable.texture(*marble)),
+                    Some(Pattern::Wood(wood)) => Box::new(hittable.texture(*wood)),
+                    Some(Pattern::Image(image)) => Box::new(hittable.texture(image.clone())),
+                    None => hittable,
+                };
+                for step in &object.moves {
+                    hittable = match *step {
+                        Move::Translate(ref offset) => {
+                            Box::new(hittable.transform(Translation(offset.sample(time))))
+                        }
+                        Move::Rotate(axis, ref angle) => {
+                            Box::new(hittable.transform(axis.rotation(angle.sample(time))))
+                        }
+                        Move::Spin(axis, period) => {
+                            let turns = (time / period).rem_euclid(1.0);
+                            Box::new(hittable.transform(axis.rotation(turns * TAU)))
+                        }
+                        Move::Scale(ref factors) => {
+                            Box::new(hittable.transform(Matrix::scale(factors.sample(time))))
+                        }
+                    };
+                }
+                hittable
+            })
+            .collect()
+    }
+}
+
+/// The words of a line of a description, after its keyword.
+struct Values<'a> {
+    words: std::str::SplitWhitespace<'a>,
+    /// The directory that files are named relative to.
+    base: &'a Path,
+    /// The files read so far, to which those the words name are added.
+    files: &'a mut Vec<PathBuf>,
+}
+
+impl<'a> Values<'a> {
+    /// Returns the next word, or an error saying that `what` is missing.
+    fn word(&mut self, what: &str) -> Result<&'a str, String> {
+        self.words
+            .next()
+            .ok_or_else(|| format!("expected {}", what))
+    }
+
+    /// Returns the next word as a finite number.
+    fn number(&mut self, what: &str) -> Result<f64, String> {
+        let word = self.word(what)?;
+        match word.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("expected {}, not `{}`", what, word)),
+        }
+    }
+
+    /// Returns the next word as an axis.
+    fn axis(&mut self) -> Result<Axis, String> {
+        match self.word("an axis")? {
+            "x" => Ok(Axis::X),
+            "y" => Ok(Axis::Y),
+            "z" => Ok(Axis::Z),
+            word => Err(format!("expected an axis x, y or z, not `{}`", word)),
+        }
+    }
+
+    /// Returns the next word as an angle in degrees, in radians.
+    fn angle(&mut self) -> Result<f64, String> {
+        Ok(self.number("an angle")?.to_radians())
+    }
+
+    /// Returns the next three words as a vector.
+    fn vector(&mut self) -> Result<Vector, String> {
+        Ok(Vector(
+            self.number("x")?,
+            self.number("y")?,
+            self.number("z")?,
+        ))
+    }
+
+    /// Returns the next word as a factor to scale by along every axis, or the next
+    /// three as factors along each axis.
+    fn factors(&mut self) -> Result<Vector, String> {
+        let x = self.number("a factor")?;
+        let factors = match self.words.clone().next() {
+            Some(_) => Vector(x, self.number("y")?, self.number("z")?),
+            None => Vector(x, x, x),
+        };
+        if factors.0 * factors.1 * factors.2 == 0.0 {
+            return Err("cannot scale by 0".to_owned());
+        }
+        Ok(factors)
+    }
+
+    /// Returns the next word as the name of a color, or the next three as its red,
+    /// green and blue components.
+    fn color(&mut self) -> Result<Color, String> {
+        let first = self.word("a color")?;
+        match named_color(first) {
+            Some(color) => Ok(color),
+            None => {
+                let red = match first.parse::<f64>() {
+                    Ok(red) if red.is_finite() => red,
+                    _ => return Err(format!("unknown color `{}`", first)),
+                };
+                Ok(Color(red, self.number("green")?, self.number("blue")?))
+            }
+        }
+    }
+
+    /// Returns the next word as the name of a color, or the next three as its
+    /// components, or white if there are no words left.
+    fn light_color(&mut self) -> Result<Color, String> {
+        match self.words.clone().next() {
+            Some(_) => self.color(),
+            None => Ok(Color::WHITE),
+        }
+    }
+
+    /// Returns the next word as the name of a material, changed by the settings and
+    /// values in the words after it.
+    fn material(&mut self) -> Result<Material, String> {
+        let mut material = match self.word("a material")? {
+            "matte" => Material::MATTE,
+            "glossy" => Material::GLOSSY,
+            "mirror" => Material::MIRROR,
+            "glass" => Material::glass(1.5),
+            word => {
+                return Err(format!(
+                    "unknown material `{}`, expected matte, glossy, mirror or glass",
+                    word
+                ))
+            }
+        };
+        while let Some(key) = self.words.next() {
+            if key == "emit" {
+                material.emission = self.color()?;
+                continue;
+            }
+            let setting = match key {
+                "diffuse" => &mut material.diffuse,
+                "specular" => &mut material.specular,
+                "shininess" => &mut material.shininess,
+                "reflect" => &mut material.reflectivity,
+                "clear" => &mut material.transparency,
+                "refract" => &mut material.refractive_index,
+                _ => {
+                    return Err(format!(
+                        "unknown material setting `{}`, expected diffuse, specular, shininess, \
+                         reflect, clear, refract or emit",
+                        key
+                    ))
+                }
+            };
+            *setting = match self.number("a value")? {
+                value if value >= 0.0 => value,
+                value => return Err(format!("expected a value of at least 0, not {}", value)),
+            };
+        }
+        if material.refractive_index <= 0.0 {
+            return Err("cannot refract by 0".to_owned());
+        }
+        // A surface cannot give back more light than reaches it.
+        if material.reflectivity > 1.0 || material.transparency > 1.0 {
+            return Err("reflect and clear must be from 0 to 1".to_owned());
+        }
+        if material.reflectivity + material.transparency > 1.0 {
+            return Err("reflect and clear must add up to at most 1".to_owned());
+        }
+        Ok(material)
+    }
+
+    /// Returns the next word as an easing, or linear easing if there are no words left.
+    fn easing(&mut self) -> Result<Easing, String> {
+        match self.words.next() {
+            Some(word) => word.parse().map_err(|error: EasingError| error.to_string()),
+            None => Ok(Easing::Linear),
+        }
+    }
+
+    /// Returns the next words as a texture.
+    fn texture(&mut self) -> Result<Pattern, String> {
+        let kind = self.word("a texture")?;
+        if kind == "image" {
+            return self.image().map(Pattern::Image);
+        }
+        if !["checker", "stripes", "gradient", "noise", "marble", "wood"].contains(&kind) {
+            return Err(format!(
+                "unknown texture `{}`, expected checker, stripes, gradient, noise, marble, wood \
+                 or image",
+                kind
+            ));
+        }
+        let colors = [self.color()?, self.color()?];
+        let mut count = |what| match self.number(what)? {
+            count if count > 0.0 => Ok(count),
+            count => Err(format!("expected {} above 0, not {}", what, count)),
+        };
+        Ok(match kind {
+            "checker" => Pattern::Checker(Checker {
+                colors,
+                size: (count("a count")?, count("a count")?),
+            }),
+            "stripes" => Pattern::Stripes(Stripes {
+                colors,
+                count: count("a count")?,
+            }),
+            "gradient" => Pattern::Gradient(Gradient {
+                from: colors[0],
+                to: colors[1],
+                easing: self.easing()?,
+            }),
+            _ => {
+                let scale = count("a scale")?;
+                let seed = self.seed()?;
+                match kind {
+                    "noise" => Pattern::Noise(Noise {
+                        colors,
+                        scale,
+                        seed,
+                    }),
+                    "marble" => Pattern::Marble(Marble {
+                        colors,
+                        scale,
+                        seed,
+                    }),
+                    _ => Pattern::Wood(Wood {
+                        colors,
+                        scale,
+                        seed,
+                    }),
+                }
+            }
+        })
+    }
+
+    /// Returns the next word as a seed, or 0 if there are no words left.
+    fn seed(&mut self) -> Result<u64, String> {
+        match self.words.next() {
+            Some(word) => word
+                .parse()
+                .map_err(|_| format!("expected a seed, not `{}`", word)),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns the next words as a background: `sky` between two colors, `stars` over
+    /// a color, an environment `image` or just a color.
+    fn background(&mut self) -> Result<Backdrop, String> {
+        match self.words.clone().next() {
+            Some("sky") => {
+                self.words.next();
+                Ok(Backdrop::Sky(Sky {
+                    below: self.color()?,
+                    above: self.color()?,
+                    easing: self.easing()?,
+                }))
+            }
+            Some("stars") => {
+                self.words.next();
+                let sky = self.color()?;
+                let density = match self.number("a density")? {
+                    density if (0.0..=1.0).contains(&density) => density,
+                    density => {
+                        return Err(format!("expected a density from 0 to 1, not {}", density))
+                    }
+                };
+                Ok(Backdrop::Stars(Starfield {
+                    sky,
+                    density,
+                    size: STAR_SIZE,
+                    seed: self.seed()?,
+                }))
+            }
+            Some("image") => {
+                self.words.next();
+                Ok(Backdrop::Environment(Environment::new(self.image()?)))
+            }
+            _ => Ok(Backdrop::Color(self.color()?)),
+        }
+    }
+
+    /// Returns the next words as fog: `linear` from a start to an end distance or
+    /// `exponential` with a density, then black unless it is given a color.
+    fn fog(&mut self) -> Result<Fog, String> {
+        let falloff = match self.word("linear or exponential")? {
+            "linear" => {
+                let start = self.number("a start distance")?;
+                match self.number("an end distance")? {
+                    end if end > start => Falloff::Linear { start, end },
+                    end => return Err(format!("fog cannot end at {} before {}", end, start)),
+                }
+            }
+            "exponential" => match self.number("a density")? {
+                density if density > 0.0 => Falloff::Exponential { density },
+                density => return Err(format!("invalid density {}", density)),
+            },
+            word => return Err(format!("expected linear or exponential, not `{}`", word)),
+        };
+        let color = match self.words.clone().next() {
+            Some(_) => self.color()?,
+            None => Color::BLACK,
+        };
+        Ok(Fog { color, falloff })
+    }
+
+    /// Returns the next word as the path of a PPM or PGM image, read into a texture
+    /// filtered and wrapped as the words after it say.
+    fn image(&mut self) -> Result<ImageTexture, String> {
+        let path = self.base.join(self.word("the path of an image")?);
+        self.files.push(path.clone());
+        let image = File::open(&path)
+            .and_then(|file| ppm::decode(BufReader::new(file)))
+            .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+        let mut texture = ImageTexture::new(image);
+        for word in self.words.by_ref() {
+            match word {
+                "nearest" => texture.filter = Filter::Nearest,
+                "bilinear" => texture.filter = Filter::Bilinear,
+                "repeat" => texture.wrap = Wrap::Repeat,
+                "clamp" => texture.wrap = Wrap::Clamp,
+                _ => {
+                    return Err(format!(
+                        "expected nearest, bilinear, repeat or clamp, not `{}`",
+                        word
+                    ))
+                }
+            }
+        }
+        Ok(texture)
+    }
+
+    /// Adds a keyframe at `time` to `track`, with the value and then the easing from
+    /// the next words as read by `value`.
+    fn key<T: Animate>(
+        &mut self,
+        track: &mut Track<T>,
+        time: f64,
+        value: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<(), String> {
+        let value = value(self)?;
+        track.insert(Keyframe {
+            time,
+            value,
+            easing: self.easing()?,
+        });
+        Ok(())
+    }
+
+    /// Fails if there are words left.
+    fn end(mut self) -> Result<(), String> {
+        match self.words.next() {
+            Some(word) => Err(format!("unexpected `{}`", word)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the color called `name`.
+fn named_color(name: &str) -> Option<Color> {
+    Some(match name {
+        "black" => Color::BLACK,
+        "red" => Color::RED,
+        "green" => Color::GREEN,
+        "yellow" => Color::YELLOW,
+        "blue" => Color::BLUE,
+        "magenta" => Color::MAGENTA,
+        "cyan" => Color::CYAN,
+        "white" => Color::WHITE,
+        _ => return None,
+    })
+}
+
+/// Parses a line starting with `keyword` into `description`.
+fn parse_line(
+    description: &mut Description,
+    keyword: &str,
+    mut values: Values,
+) -> Result<(), String> {
+    let object = description.objects.last_mut();
+    match (keyword, object) {
+        ("camera", _) => {
+            while let Some(key) = values.words.next() {
+                match key {
+                    "yaw" => description.camera.yaw = values.number("an angle")?.to_radians(),
+                    "pitch" => description.camera.pitch = values.number("an angle")?.to_radians(),
+                    "distance" => match values.number("a distance")? {
+                        distance if distance > 0.0 => description.camera.distance = distance,
+                        distance => return Err(format!("invalid distance {}", distance)),
+                    },
+                    _ => {
+                        return Err(format!(
+                            "unknown camera setting `{}`, expected yaw, pitch or distance",
+                            key
+                        ))
+                    }
+                }
+            }
+            return Ok(());
+        }
+        ("light", _) => {
+            let light = match values.word("point or sun")? {
+                "point" => Light::Point {
+                    position: values.vector()?,
+                    color: values.light_color()?,
+                },
+                "sun" => Light::Directional {
+                    direction: match values.vector()? {
+                        Vector::ZERO => return Err("a sun needs a direction".to_owned()),
+                        direction => direction,
+                    },
+                    color: values.light_color()?,
+                },
+                word => return Err(format!("expected point or sun, not `{}`", word)),
+            };
+            description.lighting.lights.push(light);
+        }
+        ("ambient", _) => description.lighting.ambient = values.color()?,
+        ("background", _) => description.background = Some(values.background()?),
+        ("fog", _) => description.fog = Some(values.fog()?),
+        ("cube", _) => description.objects.push(Object::new(Shape::Cube)),
+        ("torus", _) => {
+            let radius_major = values.number("a major radius")?;
+            let radius_minor = values.number("a minor radius")?;
+            if radius_major <= 0.0 || radius_minor <= 0.0 {
+                return Err("the radii of a torus must be positive".to_owned());
+            }
+            description.objects.push(Object::new(Shape::Torus(Torus {
+                radius_major,
+                radius_minor,
+            })));
+        }
+        (
+            "name" | "color" | "texture" | "material" | "translate" | "rotate" | "spin" | "scale"
+            | "key" | "repeat",
+            None,
+        ) => {
+            return Err(format!("`{}` must follow a shape", keyword));
+        }
+        ("name", Some(object)) => object.name = Some(values.word("a name")?.to_owned()),
+        ("color", Some(object)) => {
+            object.color = Some(Track::new(values.color()?));
+            object.keyed = Some(Keyed::Color);
+        }
+        ("texture", Some(object)) => object.texture = Some(values.texture()?),
+        ("material", Some(object)) => object.material = values.material()?,
+        ("translate", Some(object)) => {
+            let offset = Track::new(values.vector()?);
+            object.moves.push(Move::Translate(offset));
+            object.keyed = Some(Keyed::Move);
+        }
+        ("rotate", Some(object)) => {
+            let axis = values.axis()?;
+            object
+                .moves
+                .push(Move::Rotate(axis, Track::new(values.angle()?)));
+            object.keyed = Some(Keyed::Move);
+        }
+        ("spin", Some(object)) => {
+            let axis = values.axis()?;
+            match values.number("a period")? {
+                period if period != 0.0 => object.moves.push(Move::Spin(axis, period)),
+                _ => return Err("the period of a spin cannot be 0".to_owned()),
+            }
+            object.keyed = None;
+        }
+        ("scale", Some(object)) => {
+            object
+                .moves
+                .push(Move::Scale(Track::new(values.factors()?)));
+            object.keyed = Some(Keyed::Move);
+        }
+        ("key", Some(object)) => {
+            let time = values.number("a time")?;
+            match (object.keyed, &mut object.color, object.moves.last_mut()) {
+                (Some(Keyed::Color), Some(track), _) => values.key(track, time, Values::color)?,
+                (Some(Keyed::Move), _, Some(Move::Translate(track))) => {
+                    values.key(track, time, Values::vector)?;
+                }
+                (Some(Keyed::Move), _, Some(Move::Rotate(_, track))) => {
+                    values.key(track, time, Values::angle)?;
+                }
+                (Some(Keyed::Move), _, Some(Move::Scale(track))) => {
+                    values.key(track, time, Values::factors)?;
+                }
+                _ => return Err("`key` must follow color, translate, rotate or scale".to_owned()),
+            }
+        }
+        ("repeat", Some(object)) => {
+            let repeat = match values.word("hold, loop or ping-pong")? {
+                "hold" => Repeat::Hold,
+                "loop" => Repeat::Loop,
+                "ping-pong" => Repeat::PingPong,
+                word => return Err(format!("expected hold, loop or ping-pong, not `{}`", word)),
+            };
+            match object.repeat() {
+                Some(keyed) => *keyed = repeat,
+                None => {
+                    return Err("`repeat` must follow color, translate, rotate or scale".to_owned())
+                }
+            }
+        }
+        _ => return Err(format!("unknown keyword `{}`", keyword)),
+    }
+    values.end()
+}
+
+impl Default for Description {
+    /// Returns an empty description, with the camera 3 units away from the origin.
+    fn default() -> Self {
+        Self {
+            camera: Orbit {
+                target: Vector::ZERO,
+                yaw: 0.0,
+                pitch: 0.0,
+                distance: 3.0,
+            },
+            lighting: Lighting::new(Vec::new()),
+            background: None,
+            fog: None,
+            objects: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Description {
+    type Err = SceneError;
+
+    /// Parses a description, with files that it names relative to the current
+    /// directory.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Path::new(""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{Axis, Backdrop, Description, Falloff, Fog, Move, Pattern, Shape, STAR_SIZE};
+    use crate::{
+        util::check_about, Checker, Color, Easing, EasingCurve, Filter, Gradient, Hittable, Light,
+        Material, Noise, ObjectId, Ray, Repeat, Sky, Starfield, Track, Vector, Wood, Wrap,
+    };
+
+    #[test]
+    fn parse() {
+        let description: Description = "
+            camera yaw 90 distance 4 # looking from the right
+            cube
+                name box
+                color 0.5 0.25 1
+                scale 2
+                rotate z 90
+                texture checker white 0 0 1 4 2
+            torus 1 0.5
+                color yellow
+                texture gradient red blue quad-in
+                spin x -3
+                scale 1 2 3
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(description.camera.distance, 4.0);
+        assert_eq!(description.camera.yaw, 90f64.to_radians());
+        let [cube, torus] = &description.objects[..] else {
+            panic!("expected two objects");
+        };
+        assert_eq!(cube.shape, Shape::Cube);
+        assert_eq!(cube.name.as_deref(), Some("box"));
+        assert_eq!(cube.color, Some(Track::new(Color(0.5, 0.25, 1.0))));
+        assert_eq!(
+            cube.moves,
+            [
+                Move::Scale(Track::new(Vector(2.0, 2.0, 2.0))),
+                Move::Rotate(Axis::Z, Track::new(90f64.to_radians()))
+            ]
+        );
+        assert!(matches!(torus.shape, Shape::Torus(_)));
+        assert_eq!(
+            cube.texture,
+            Some(Pattern::Checker(Checker {
+                colors: [Color::WHITE, Color::BLUE],
+                size: (4.0, 2.0),
+            }))
+        );
+        assert_eq!(torus.color, Some(Track::new(Color::YELLOW)));
+        assert_eq!(
+            torus.texture,
+            Some(Pattern::Gradient(Gradient {
+                from: Color::RED,
+                to: Color::BLUE,
+                easing: Easing::In(EasingCurve::Quad),
+            }))
+        );
+        let solid: Description = "
+            torus 1 0.5
+                texture noise black white 0.5
+            cube
+                texture wood red yellow 0.1 4
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            solid.objects[0].texture,
+            Some(Pattern::Noise(Noise {
+                colors: [Color::BLACK, Color::WHITE],
+                scale: 0.5,
+                seed: 0,
+            }))
+        );
+        assert_eq!(
+            solid.objects[1].texture,
+            Some(Pattern::Wood(Wood {
+                colors: [Color::RED, Color::YELLOW],
+                scale: 0.1,
+                seed: 4,
+            }))
+        );
+        assert_eq!(
+            torus.moves,
+            [
+                Move::Spin(Axis::X, -3.0),
+                Move::Scale(Track::new(Vector(1.0, 2.0, 3.0)))
+            ]
+        );
+    }
+
+    #[test]
+    fn keyframes() {
+        let description: Description = "
+            cube
+                color red
+                    key 2 blue sin-in-out
+                    repeat ping-pong
+                translate 0 0 0
+                    key 1 0 0 1
+                    key 3 0 0 3
+                rotate x 0
+                scale 1
+                    key 1 2
+        "
+        .parse()
+        .unwrap();
+        let [cube] = &description.objects[..] else {
+            panic!("expected one object");
+        };
+        assert_eq!(
+            cube.color,
+            Some(
+                Track::new(Color::RED)
+                    .key(2.0, Color::BLUE, Easing::InOut(EasingCurve::Sin))
+                    .repeat(Repeat::PingPong)
+            )
+        );
+        let [Move::Translate(offset), Move::Rotate(_, angle), Move::Scale(factors)] =
+            &cube.moves[..]
+        else {
+            panic!("expected three moves");
+        };
+        check_about(offset.sample(2.0).z(), 2.0);
+        assert_eq!(offset.repeat, Repeat::Hold);
+        assert_eq!(angle.keyframes().len(), 1);
+        assert_eq!(factors.sample(1.0), Vector(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn at() {
+        let description: Description = "cube\nname box\ncolor green".parse().unwrap();
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        let hit = description.at(0.0).hit(incidence).unwrap();
+        assert_eq!(hit.color, Color::GREEN);
+        assert_eq!(hit.object, Some(ObjectId::named("box")));
+        assert_eq!(hit.t, 2.5);
+        let textured: Description = "cube\ncolor green\ntexture checker white black 4 4"
+            .parse()
+            .unwrap();
+        let hit = textured.at(0.0).hit(incidence).unwrap();
+        assert_eq!(hit.color, Color::WHITE);
+        let empty: Description = "# nothing\n".parse().unwrap();
+        assert!(empty.at(0.0).hit(incidence).is_none());
+    }
+
+    #[test]
+    fn image() {
+        let directory = env::temp_dir().join(format!("shapes-image-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("logo.ppm"), b"P3 1 1 255 0 255 0").unwrap();
+        let path = directory.join("logo.scene");
+        fs::write(&path, "cube\ntexture image logo.ppm nearest clamp\n").unwrap();
+        let description = Description::load(&path).unwrap();
+        let Some(Pattern::Image(texture)) = &description.objects[0].texture else {
+            panic!("expected an image texture");
+        };
+        assert_eq!(texture.filter, Filter::Nearest);
+        assert_eq!(texture.wrap, Wrap::Clamp);
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        assert_eq!(
+            description.at(0.0).hit(incidence).unwrap().color,
+            Color::GREEN
+        );
+
+        // Images are named relative to the scene file rather than the current directory.
+        let error = "cube\ntexture image logo.ppm"
+            .parse::<Description>()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("line 2: could not read logo.ppm: "),
+            "{}",
+            error
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn lighting() {
+        let description: Description = "
+            light point 1 2 3
+            light sun 0 1 0 0.5 0.5 0.5
+            ambient black
+            cube
+                material glass refract 1.3 clear 0.8
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            description.lighting.lights,
+            [
+                Light::Point {
+                    position: Vector(1.0, 2.0, 3.0),
+                    color: Color::WHITE,
+                },
+                Light::Directional {
+                    direction: Vector(0.0, 1.0, 0.0),
+                    color: Color(0.5, 0.5, 0.5),
+                },
+            ]
+        );
+        assert_eq!(description.lighting.ambient, Color::BLACK);
+        assert_eq!(
+            description.objects[0].material,
+            Material {
+                transparency: 0.8,
+                refractive_index: 1.3,
+                ..Material::glass(1.5)
+            }
+        );
+
+        // With lights, objects are shaded rather than flat, such as dark when lit from
+        // behind.
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        let lit: Description = "light sun 0 -1 0\nambient black\ncube\ncolor green"
+            .parse()
+            .unwrap();
+        assert_eq!(lit.at(0.0).hit(incidence).unwrap().color, Color::BLACK);
+        let flat: Description = "ambient black\ncube\ncolor green".parse().unwrap();
+        assert_eq!(flat.at(0.0).hit(incidence).unwrap().color, Color::GREEN);
+
+        // Path traced, glowing objects light the scene, and unlit scenes have a sky.
+        let glowing: Description = "cube\nmaterial matte diffuse 0 emit 1 0.5 0"
+            .parse()
+            .unwrap();
+        assert_eq!(glowing.objects[0].material.emission, Color(1.0, 0.5, 0.0));
+        let hit = glowing.path_traced(0.0).hit(incidence).unwrap();
+        assert_eq!(hit.color, Color(1.0, 0.5, 0.0));
+        let hit = flat.path_traced(0.0).hit(incidence).unwrap();
+        assert_eq!(hit.color, Color::GREEN);
+    }
+
+    #[test]
+    fn background() {
+        let sky: Description = "background sky black blue\ncube".parse().unwrap();
+        assert_eq!(
+            sky.background,
+            Some(Backdrop::Sky(Sky {
+                below: Color::BLACK,
+                above: Color::BLUE,
+                easing: Easing::Linear,
+            }))
+        );
+        let up = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 0.0, 1.0),
+        };
+        let hit = sky.at(0.0).hit(up).unwrap();
+        assert_eq!(hit.color, Color::BLUE);
+        assert_eq!(hit.t, f64::INFINITY);
+
+        let stars: Description = "background stars 0 0 0.1 0.5 4".parse().unwrap();
+        assert_eq!(
+            stars.background,
+            Some(Backdrop::Stars(Starfield {
+                sky: Color(0.0, 0.0, 0.1),
+                density: 0.5,
+                size: STAR_SIZE,
+                seed: 4,
+            }))
+        );
+        let plain: Description = "background red".parse().unwrap();
+        assert_eq!(plain.background, Some(Backdrop::Color(Color::RED)));
+        // Lit and path traced, objects reflect the background.
+        let mirror: Description = "light sun 0 1 0\nbackground green\ncube\nmaterial mirror"
+            .parse()
+            .unwrap();
+        let ahead = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.2, 1.0, 0.1),
+        };
+        assert!(mirror.at(0.0).hit(ahead).unwrap().color.1 > 0.8);
+        assert!(mirror.path_traced(0.0).hit(ahead).unwrap().color.1 > 0.8);
+    }
+
+    #[test]
+    fn fog() {
+        let foggy: Description = "fog linear 1 3 white\ncube\ncolor black".parse().unwrap();
+        assert_eq!(
+            foggy.fog,
+            Some(Fog {
+                color: Color::WHITE,
+                falloff: Falloff::Linear {
+                    start: 1.0,
+                    end: 3.0
+                },
+            })
+        );
+        let ahead = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        // The front of the cube is 2.5 away, three quarters of the way into the fog.
+        assert_eq!(
+            foggy.at(0.0).hit(ahead).unwrap().color,
+            Color(0.75, 0.75, 0.75)
+        );
+        let up = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 0.0, 1.0),
+        };
+        assert!(foggy.at(0.0).hit(up).is_none());
+        // The background shows through, where there is nothing for fog to hide.
+        let backed: Description = "fog exponential 0.5 white\nbackground red\ncube"
+            .parse()
+            .unwrap();
+        let hit = backed.at(0.0).hit(up).unwrap();
+        assert_eq!(hit.color, Color::RED);
+        assert_eq!(hit.t, f64::INFINITY);
+        let fogged = backed.at(0.0).hit(ahead).unwrap().color;
+        check_about(fogged.1, 1.0 - (-1.25f64).exp());
+        let thick: Description = "fog exponential 0.5\ncube\ncolor white".parse().unwrap();
+        let color = thick.path_traced(0.0).hit(ahead).unwrap().color;
+        assert!(color.0 < 0.5, "{:?}", color);
+    }
+
+    #[test]
+    fn errors() {
+        for (text, error) in [
+            ("sphere", "line 1: unknown keyword `sphere`"),
+            ("\ncolor red", "line 2: `color` must follow a shape"),
+            ("cube\ncolor red 1", "line 2: unexpected `1`"),
+            ("cube\ncolor 1 0", "line 2: expected blue"),
+            ("cube\ncolor nan 0 0", "line 2: unknown color `nan`"),
+            ("cube\ncolor inf 1 1", "line 2: unknown color `inf`"),
+            (
+                "cube\nrotate w 4",
+                "line 2: expected an axis x, y or z, not `w`",
+            ),
+            (
+                "torus 1 -1",
+                "line 1: the radii of a torus must be positive",
+            ),
+            ("cube\nscale 1 0 1", "line 2: cannot scale by 0"),
+            ("fog linear 3 1", "line 1: fog cannot end at 1 before 3"),
+            ("fog exponential 0", "line 1: invalid density 0"),
+            (
+                "fog thick 1",
+                "line 1: expected linear or exponential, not `thick`",
+            ),
+            (
+                "light lamp 0 0 0",
+                "line 1: expected point or sun, not `lamp`",
+            ),
+            ("light sun 0 0 0", "line 1: a sun needs a direction"),
+            (
+                "cube\nmaterial wood",
+                "line 2: unknown material `wood`, expected matte, glossy, mirror or glass",
+            ),
+            (
+                "cube\nmaterial glass shine 2",
+                "line 2: unknown material setting `shine`, expected diffuse, specular, \
+                 shininess, reflect, clear, refract or emit",
+            ),
+            (
+                "background stars black 2",
+                "line 1: expected a density from 0 to 1, not 2",
+            ),
+            ("background sky red", "line 1: expected a color"),
+            (
+                "cube\nmaterial glass refract 0",
+                "line 2: cannot refract by 0",
+            ),
+            (
+                "cube\nmaterial mirror reflect 1.5",
+                "line 2: reflect and clear must be from 0 to 1",
+            ),
+            (
+                "cube\nmaterial matte clear 2",
+                "line 2: reflect and clear must be from 0 to 1",
+            ),
+            (
+                "cube\nmaterial glass reflect 0.5",
+                "line 2: reflect and clear must add up to at most 1",
+            ),
+            (
+                "cube\ntexture oak red blue",
+                "line 2: unknown texture `oak`, expected checker, stripes, gradient, noise, \
+                 marble, wood or image",
+            ),
+            (
+                "cube\ntexture stripes red blue 0",
+                "line 2: expected a count above 0, not 0",
+            ),
+            (
+                "torus 1 1\ntexture noise red blue 1 -1",
+                "line 2: expected a seed, not `-1`",
+            ),
+            (
+                "camera zoom 2",
+                "line 1: unknown camera setting `zoom`, expected yaw, pitch or distance",
+            ),
+            ("cube\nspin x nan", "line 2: expected a period, not `nan`"),
+            (
+                "cube\nkey 1 red",
+                "line 2: `key` must follow color, translate, rotate or scale",
+            ),
+            (
+                "cube\nspin x 1\nkey 1 2",
+                "line 3: `key` must follow color, translate, rotate or scale",
+            ),
+            (
+                "cube\nrotate x 0\nkey 1 90 bounce",
+                "line 3: unknown easing `bounce`, expected linear, cubic-bezier(x1,y1,x2,y2) with \
+                 x1 and x2 from 0 to 1, or one of sin, quad, cubic, quart, quint, expo, circ, \
+                 back, elastic or bounce followed by -in, -out or -in-out",
+            ),
+            (
+                "cube\ncolor red\nrepeat forever",
+                "line 3: expected hold, loop or ping-pong, not `forever`",
+            ),
+        ] {
+            let result = text.parse::<Description>();
+            assert_eq!(result.unwrap_err().to_string(), error, "parsing {:?}", text);
+        }
+    }
+}
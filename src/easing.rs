@@ -1,5 +1,355 @@
-use std::f64::consts::PI;
+use std::{
+    error::Error,
+    f64::consts::{PI, TAU},
+    fmt,
+    str::FromStr,
+};
 
-pub(crate) fn ease_sin_in_out(x: f64) -> f64 {
-    -((PI * x).cos() - 1.0) / 2.0
+/// A family of easing curves, each of which can ease in, out or both, as shown on
+/// [easings.net](https://easings.net/).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EasingCurve {
+    /// A quarter of a sine wave.
+    Sin,
+    /// The square of the progress.
+    Quad,
+    /// The cube of the progress.
+    Cubic,
+    /// The fourth power of the progress.
+    Quart,
+    /// The fifth power of the progress.
+    Quint,
+    /// An exponential curve.
+    Expo,
+    /// A quarter of a circle.
+    Circ,
+    /// Backing up a little before setting off.
+    Back,
+    /// Winding up like a spring before setting off.
+    Elastic,
+    /// Bouncing like a ball before setting off.
+    Bounce,
+}
+
+impl EasingCurve {
+    /// All the families of curves.
+    pub const ALL: [Self; 10] = [
+        Self::Sin,
+        Self::Quad,
+        Self::Cubic,
+        Self::Quart,
+        Self::Quint,
+        Self::Expo,
+        Self::Circ,
+        Self::Back,
+        Self::Elastic,
+        Self::Bounce,
+    ];
+
+    /// The overshoot of the back curves.
+    const BACK: f64 = 1.70158;
+
+    /// Returns the name of this family, as used in the names of easings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sin => "sin",
+            Self::Quad => "quad",
+            Self::Cubic => "cubic",
+            Self::Quart => "quart",
+            Self::Quint => "quint",
+            Self::Expo => "expo",
+            Self::Circ => "circ",
+            Self::Back => "back",
+            Self::Elastic => "elastic",
+            Self::Bounce => "bounce",
+        }
+    }
+
+    /// Returns the progress at `x` of the curve of this family that eases in, starting
+    /// slowly.
+    fn ease_in(self, x: f64) -> f64 {
+        match self {
+            Self::Sin => 1.0 - (x * PI / 2.0).cos(),
+            Self::Quad => x.powi(2),
+            Self::Cubic => x.powi(3),
+            Self::Quart => x.powi(4),
+            Self::Quint => x.powi(5),
+            Self::Expo if x <= 0.0 => 0.0,
+            Self::Expo => 2f64.powf(10.0 * x - 10.0),
+            Self::Circ => 1.0 - (1.0 - x.powi(2)).max(0.0).sqrt(),
+            Self::Back => (Self::BACK + 1.0) * x.powi(3) - Self::BACK * x.powi(2),
+            Self::Elastic if x <= 0.0 || x >= 1.0 => x.clamp(0.0, 1.0),
+            Self::Elastic => -(2f64.powf(10.0 * x - 10.0) * ((x * 10.0 - 10.75) * TAU / 3.0).sin()),
+            Self::Bounce => 1.0 - Self::Bounce.ease_out(1.0 - x),
+        }
+    }
+
+    /// Returns the progress at `x` of the curve of this family that eases out, ending
+    /// slowly.
+    fn ease_out(self, x: f64) -> f64 {
+        match self {
+            Self::Bounce => {
+                // Four parabolas, each a lower bounce than the one before.
+                let (scale, width) = (7.5625, 2.75);
+                let (center, height) = if x < 1.0 / width {
+                    (0.0, 0.0)
+                } else if x < 2.0 / width {
+                    (1.5, 0.75)
+                } else if x < 2.5 / width {
+                    (2.25, 0.9375)
+                } else {
+                    (2.625, 0.984375)
+                };
+                scale * (x - center / width).powi(2) + height
+            }
+            _ => 1.0 - self.ease_in(1.0 - x),
+        }
+    }
+
+    /// Returns the progress at `x` of the curve of this family that eases in and out,
+    /// starting and ending slowly.
+    fn ease_in_out(self, x: f64) -> f64 {
+        // The back and elastic curves overshoot less when they ease both ways.
+        match self {
+            Self::Back => {
+                let overshoot = Self::BACK * 1.525;
+                let curve = |x: f64| x.powi(2) * ((overshoot + 1.0) * x - overshoot);
+                if x < 0.5 {
+                    curve(2.0 * x) / 2.0
+                } else {
+                    1.0 - curve(2.0 - 2.0 * x) / 2.0
+                }
+            }
+            Self::Elastic if x <= 0.0 || x >= 1.0 => x.clamp(0.0, 1.0),
+            Self::Elastic => {
+                let wave = ((20.0 * x - 11.125) * TAU / 4.5).sin();
+                if x < 0.5 {
+                    -(2f64.powf(20.0 * x - 10.0) * wave) / 2.0
+                } else {
+                    2f64.powf(-20.0 * x + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+            _ if x < 0.5 => self.ease_in(2.0 * x) / 2.0,
+            _ => 1.0 - self.ease_in(2.0 - 2.0 * x) / 2.0,
+        }
+    }
+}
+
+/// A curve that eases the progress through a segment of an animation, taking how far
+/// it is through the segment in time, from 0 to 1, to how far it is through the change
+/// in value.
+///
+/// Easings are named like `linear`, `quad-in`, `bounce-out`, `sin-in-out` or
+/// `cubic-bezier(0.25,0.1,0.25,1)`, which is how they are [parsed](FromStr) and
+/// [displayed](fmt::Display).
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub enum Easing {
+    /// A constant rate of change.
+    #[default]
+    Linear,
+    /// Starting slowly along a curve.
+    In(EasingCurve),
+    /// Ending slowly along a curve.
+    Out(EasingCurve),
+    /// Starting and ending slowly along a curve.
+    InOut(EasingCurve),
+    /// A cubic Bézier curve from (0, 0) to (1, 1) with the control points (x1, y1) and
+    /// (x2, y2), like the `cubic-bezier` timing function of CSS.
+    ///
+    /// The x coordinates of the control points are between 0 and 1, so that there is one
+    /// point of the curve for each time.
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl Easing {
+    /// Returns the progress through the change in value at `x` of the way through a
+    /// segment, starting at 0 and ending at 1.
+    pub fn ease(self, x: f64) -> f64 {
+        match self {
+            Self::Linear => x,
+            Self::In(curve) => curve.ease_in(x),
+            Self::Out(curve) => curve.ease_out(x),
+            Self::InOut(curve) => curve.ease_in_out(x),
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                // The curve is a function of a parameter t, for which both x and y go from
+                // 0 to 1. As x only ever grows with t, the t at `x` is found by bisection.
+                let bezier = |a: f64, b: f64, t: f64| {
+                    3.0 * a * (1.0 - t).powi(2) * t + 3.0 * b * (1.0 - t) * t.powi(2) + t.powi(3)
+                };
+                let x = x.clamp(0.0, 1.0);
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..40 {
+                    let t = (low + high) / 2.0;
+                    if bezier(x1, x2, t) < x {
+                        low = t;
+                    } else {
+                        high = t;
+                    }
+                }
+                bezier(y1, y2, (low + high) / 2.0)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Linear => f.write_str("linear"),
+            Self::In(curve) => write!(f, "{}-in", curve.name()),
+            Self::Out(curve) => write!(f, "{}-out", curve.name()),
+            Self::InOut(curve) => write!(f, "{}-in-out", curve.name()),
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                write!(f, "cubic-bezier({},{},{},{})", x1, y1, x2, y2)
+            }
+        }
+    }
+}
+
+impl FromStr for Easing {
+    type Err = EasingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || EasingError(s.to_owned());
+        if s == "linear" {
+            return Ok(Self::Linear);
+        }
+        if let Some(points) = s
+            .strip_prefix("cubic-bezier(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            let points = points
+                .split(',')
+                .map(|point| point.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| error())?;
+            return match points[..] {
+                [x1, y1, x2, y2]
+                    if (0.0..=1.0).contains(&x1)
+                        && (0.0..=1.0).contains(&x2)
+                        && y1.is_finite()
+                        && y2.is_finite() =>
+                {
+                    Ok(Self::CubicBezier(x1, y1, x2, y2))
+                }
+                _ => Err(error()),
+            };
+        }
+
+        let (name, ease): (_, fn(EasingCurve) -> Self) =
+            if let Some(name) = s.strip_suffix("-in-out") {
+                (name, Self::InOut)
+            } else if let Some(name) = s.strip_suffix("-in") {
+                (name, Self::In)
+            } else if let Some(name) = s.strip_suffix("-out") {
+                (name, Self::Out)
+            } else {
+                return Err(error());
+            };
+        EasingCurve::ALL
+            .into_iter()
+            .find(|curve| curve.name() == name)
+            .map(ease)
+            .ok_or_else(error)
+    }
+}
+
+/// An easing name that is not known.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EasingError(String);
+
+impl fmt::Display for EasingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown easing `{}`, expected linear, cubic-bezier(x1,y1,x2,y2) with x1 and x2 \
+             from 0 to 1, or one of ",
+            self.0
+        )?;
+        let last = EasingCurve::ALL.len() - 1;
+        for (i, curve) in EasingCurve::ALL.into_iter().enumerate() {
+            let separator = match i {
+                0 => "",
+                _ if i == last => " or ",
+                _ => ", ",
+            };
+            write!(f, "{}{}", separator, curve.name())?;
+        }
+        f.write_str(" followed by -in, -out or -in-out")
+    }
+}
+
+impl Error for EasingError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Easing, EasingCurve};
+    use crate::util::check_about;
+
+    #[test]
+    fn ease() {
+        let mut easings = vec![Easing::Linear, Easing::CubicBezier(0.25, 0.1, 0.25, 1.0)];
+        for curve in EasingCurve::ALL {
+            easings.extend([Easing::In(curve), Easing::Out(curve), Easing::InOut(curve)]);
+        }
+        for easing in easings {
+            check_about(easing.ease(0.0), 0.0);
+            check_about(easing.ease(1.0), 1.0);
+            // Each in-out curve is the in curve and then the out curve, at half the size.
+            if let Easing::InOut(curve) = easing {
+                if !matches!(curve, EasingCurve::Back | EasingCurve::Elastic) {
+                    check_about(easing.ease(0.25), Easing::In(curve).ease(0.5) / 2.0);
+                    check_about(easing.ease(0.75), 0.5 + Easing::Out(curve).ease(0.5) / 2.0);
+                }
+                check_about(easing.ease(0.5), 0.5);
+            }
+            assert_eq!(easing.to_string().parse(), Ok(easing));
+        }
+
+        check_about(Easing::InOut(EasingCurve::Sin).ease(0.25), 0.1464);
+        check_about(Easing::In(EasingCurve::Cubic).ease(0.5), 0.125);
+        check_about(Easing::Out(EasingCurve::Quad).ease(0.5), 0.75);
+        check_about(Easing::In(EasingCurve::Expo).ease(0.5), 0.03125);
+        check_about(Easing::Out(EasingCurve::Circ).ease(0.5), 0.8660);
+        check_about(Easing::In(EasingCurve::Back).ease(0.5), -0.0877);
+        check_about(Easing::Out(EasingCurve::Elastic).ease(0.1), 1.25);
+        check_about(Easing::Out(EasingCurve::Bounce).ease(0.5), 0.7656);
+        check_about(Easing::In(EasingCurve::Bounce).ease(0.5), 0.2344);
+        check_about(Easing::InOut(EasingCurve::Back).ease(0.25), -0.0997);
+        check_about(Easing::InOut(EasingCurve::Elastic).ease(0.75), 0.9880);
+
+        // The `ease` of CSS.
+        let css = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+        check_about(css.ease(0.25), 0.4085);
+        check_about(css.ease(0.5), 0.8024);
+        check_about(Easing::CubicBezier(0.5, 0.0, 0.5, 1.0).ease(0.5), 0.5);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("linear".parse(), Ok(Easing::Linear));
+        assert_eq!("quint-in".parse(), Ok(Easing::In(EasingCurve::Quint)));
+        assert_eq!(
+            "elastic-in-out".parse(),
+            Ok(Easing::InOut(EasingCurve::Elastic))
+        );
+        assert_eq!(
+            "cubic-bezier(0.42, 0, 1, 1)".parse(),
+            Ok(Easing::CubicBezier(0.42, 0.0, 1.0, 1.0))
+        );
+        for name in [
+            "bounce",
+            "sine-in",
+            "cubic-bezier(2,0,1,1)",
+            "cubic-bezier(1,1)",
+        ] {
+            assert!(name.parse::<Easing>().is_err(), "parsing {}", name);
+        }
+        assert_eq!(
+            "wobble-in".parse::<Easing>().unwrap_err().to_string(),
+            "unknown easing `wobble-in`, expected linear, cubic-bezier(x1,y1,x2,y2) with x1 \
+             and x2 from 0 to 1, or one of sin, quad, cubic, quart, quint, expo, circ, back, \
+             elastic or bounce followed by -in, -out or -in-out"
+        );
+    }
 }
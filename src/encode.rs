@@ -0,0 +1,47 @@
+//! Encoders that turn [`Framebuffer`](crate::Framebuffer)s and [`Image`]s into bytes.
+
+pub mod ansi;
+pub mod asciicast;
+pub mod gif;
+pub mod png;
+pub mod ppm;
+pub mod text;
+mod zlib;
+
+use std::path::Path;
+
+/// A file format that can be rendered to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Format {
+    /// A still PPM image.
+    Ppm,
+    /// A still PNG image.
+    Png,
+    /// An animated GIF.
+    Gif,
+    /// An asciicast recording of the terminal.
+    Asciicast,
+    /// A still in plain text.
+    Text,
+    /// The ANSI escape sequences that would be written to the terminal.
+    Ansi,
+}
+
+impl Format {
+    /// Returns the format matching the extension of `path`, defaulting to [`Format::Ansi`].
+    pub(crate) fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Self::Ppm,
+            "png" => Self::Png,
+            "gif" => Self::Gif,
+            "cast" => Self::Asciicast,
+            "txt" => Self::Text,
+            _ => Self::Ansi,
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::{io, str::FromStr};
+
+use super::text;
+use crate::{Cell, Color, Framebuffer};
+
+/// How the cells of a [`Framebuffer`] are drawn with characters.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum RenderMode {
+    /// Each character is a full block showing one cell.
+    #[default]
+    Block,
+    /// Each character is a half block showing two cells stacked on top of each other,
+    /// doubling the vertical resolution.
+    HalfBlock,
+    /// Each character is chosen by the brightness of one cell.
+    Ascii,
+}
+
+impl RenderMode {
+    /// Returns how many rows of a [`Framebuffer`] are drawn on each line.
+    pub(crate) fn rows_per_line(self) -> usize {
+        match self {
+            Self::HalfBlock => 2,
+            Self::Block | Self::Ascii => 1,
+        }
+    }
+}
+
+impl FromStr for RenderMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(Self::Block),
+            "half" => Ok(Self::HalfBlock),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(format!(
+                "unknown render mode `{}`, expected block, half or ascii",
+                s
+            )),
+        }
+    }
+}
+
+/// Which colors are written with ANSI escape sequences.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum ColorMode {
+    /// No colors at all.
+    None,
+    /// The eight basic colors, supported by nearly every terminal.
+    #[default]
+    Ansi16,
+    /// The 6x6x6 color cube of 256-color terminals.
+    Ansi256,
+    /// 24-bit colors.
+    TrueColor,
+}
+
+impl ColorMode {
+    /// Writes the SGR parameter setting the foreground, or the background, to `color`.
+    fn write_parameter(
+        self,
+        f: &mut impl io::Write,
+        color: Color,
+        background: bool,
+    ) -> io::Result<()> {
+        let base = if background { 40 } else { 30 };
+        match self {
+            Self::None => Ok(()),
+            Self::Ansi16 => write!(f, "{}", color.ansi() - 30 + base),
+            Self::Ansi256 => {
+                let [r, g, b] = color.to_rgb8().map(|c| (u16::from(c) * 5 + 127) / 255);
+                write!(f, "{};5;{}", base + 8, 16 + 36 * r + 6 * g + b)
+            }
+            Self::TrueColor => {
+                let [r, g, b] = color.to_rgb8();
+                write!(f, "{};2;{};{};{}", base + 8, r, g, b)
+            }
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "16" => Ok(Self::Ansi16),
+            "256" => Ok(Self::Ansi256),
+            "truecolor" => Ok(Self::TrueColor),
+            _ => Err(format!(
+                "unknown color mode `{}`, expected none, 16, 256 or truecolor",
+                s
+            )),
+        }
+    }
+}
+
+/// Returns the color `cell` is drawn with in `render_mode`, or [`None`] if it is left
+/// blank.
+///
+/// Blocks are drawn for cells that are at least half hit. Characters are drawn for any
+/// cell that was hit, as bright as its color blended over black by its coverage.
+fn shown(render_mode: RenderMode, cell: &Cell) -> Option<Color> {
+    match render_mode {
+        RenderMode::Block | RenderMode::HalfBlock => cell.solid(),
+        RenderMode::Ascii => cell.color.map(|_| cell.over(Color::BLACK)),
+    }
+}
+
+/// Writes a single character showing `top`, and `bottom` if there is a second row.
+fn write_char(
+    buf: &mut impl io::Write,
+    render_mode: RenderMode,
+    color_mode: ColorMode,
+    top: &Cell,
+    bottom: Option<&Cell>,
+) -> io::Result<()> {
+    let (glyph, foreground, background) = match (
+        render_mode,
+        shown(render_mode, top),
+        bottom.map(|cell| shown(render_mode, cell)),
+    ) {
+        (RenderMode::Block, Some(color), _) => ('█', Some(color), None),
+        (RenderMode::Ascii, Some(color), _) => (text::glyph(color), Some(color), None),
+        (RenderMode::HalfBlock, Some(top), Some(Some(bottom))) => {
+            if color_mode == ColorMode::None {
+                ('█', None, None)
+            } else {
+                ('▀', Some(top), Some(bottom))
+            }
+        }
+        (RenderMode::HalfBlock, Some(top), _) => ('▀', Some(top), None),
+        (RenderMode::HalfBlock, None, Some(Some(bottom))) => ('▄', Some(bottom), None),
+        (_, None, _) => (' ', None, None),
+    };
+
+    if color_mode != ColorMode::None {
+        match (foreground, background, render_mode) {
+            (Some(foreground), None, RenderMode::HalfBlock) => {
+                write!(buf, "\x1b[49;")?;
+                color_mode.write_parameter(buf, foreground, false)?;
+                write!(buf, "m")?;
+            }
+            (Some(foreground), background, _) => {
+                write!(buf, "\x1b[")?;
+                color_mode.write_parameter(buf, foreground, false)?;
+                if let Some(background) = background {
+                    write!(buf, ";")?;
+                    color_mode.write_parameter(buf, background, true)?;
+                }
+                write!(buf, "m")?;
+            }
+            (None, _, RenderMode::HalfBlock) => write!(buf, "\x1b[49m")?,
+            (None, _, _) => {}
+        }
+    }
+    write!(buf, "{}", glyph)
+}
+
+/// Writes `framebuffer` as lines of characters drawn in `render_mode`, colored with
+/// ANSI escape sequences in `color_mode`, leaving the cursor below the last line with
+/// colors reset.
+pub fn encode(
+    buf: impl io::Write,
+    framebuffer: &Framebuffer,
+    render_mode: RenderMode,
+    color_mode: ColorMode,
+) -> io::Result<()> {
+    encode_lines(buf, framebuffer, None, render_mode, color_mode)
+}
+
+/// Writes `framebuffer` like [`encode`], but with its top-left character at `column`
+/// and `line` of the terminal, counting from 0, moving the cursor to the start of each
+/// line instead of writing newlines.
+///
+/// Characters where nothing was hit are skipped over, leaving what the terminal showed
+/// there untouched. Half blocks with only one half hit still draw the other half in the
+/// default background color. The cursor is left after the last character drawn.
+pub fn encode_at(
+    buf: impl io::Write,
+    framebuffer: &Framebuffer,
+    column: usize,
+    line: usize,
+    render_mode: RenderMode,
+    color_mode: ColorMode,
+) -> io::Result<()> {
+    encode_lines(
+        buf,
+        framebuffer,
+        Some((column, line)),
+        render_mode,
+        color_mode,
+    )
+}
+
+/// Writes `framebuffer` as lines, either one after another or, at a `position`, with
+/// characters where nothing was hit skipped over.
+fn encode_lines(
+    mut buf: impl io::Write,
+    framebuffer: &Framebuffer,
+    position: Option<(usize, usize)>,
+    render_mode: RenderMode,
+    color_mode: ColorMode,
+) -> io::Result<()> {
+    let mut rows = framebuffer.rows();
+    let mut line = 0;
+    while let Some(top) = rows.next() {
+        // A missing last row of half blocks is drawn as if nothing was hit.
+        let bottom = (render_mode.rows_per_line() == 2).then(|| rows.next());
+        if let Some((column, first_line)) = position {
+            write!(buf, "\x1b[{};{}H", first_line + line + 1, column + 1)?;
+        }
+        let mut skipped = 0;
+        for (x, cell) in top.iter().enumerate() {
+            let bottom = bottom.map(|row| row.map_or(&Cell::EMPTY, |row| &row[x]));
+            let transparent = shown(render_mode, cell).is_none()
+                && bottom.is_none_or(|bottom| shown(render_mode, bottom).is_none());
+            if position.is_some() && transparent {
+                skipped += 1;
+                continue;
+            }
+            if skipped > 0 {
+                write!(buf, "\x1b[{}C", skipped)?;
+                skipped = 0;
+            }
+            write_char(&mut buf, render_mode, color_mode, cell, bottom)?;
+        }
+        if position.is_none() {
+            writeln!(buf)?;
+        }
+        line += 1;
+    }
+    if color_mode != ColorMode::None {
+        write!(buf, "\x1b[0m")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, encode_at, ColorMode, RenderMode};
+    use crate::{Color, Framebuffer};
+
+    fn framebuffer() -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(2, 2);
+        framebuffer.get_mut(0, 0).unwrap().color = Some(Color::RED);
+        framebuffer.get_mut(0, 1).unwrap().color = Some(Color::BLUE);
+        framebuffer.get_mut(1, 1).unwrap().color = Some(Color::WHITE);
+        framebuffer
+    }
+
+    fn encoded(render_mode: RenderMode, color_mode: ColorMode) -> String {
+        let mut buf = Vec::new();
+        encode(&mut buf, &framebuffer(), render_mode, color_mode).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn block() {
+        assert_eq!(
+            encoded(RenderMode::Block, ColorMode::Ansi16),
+            "\x1b[31m█ \n\x1b[34m█\x1b[37m█\n\x1b[0m"
+        );
+        assert_eq!(encoded(RenderMode::Block, ColorMode::None), "█ \n██\n");
+        assert_eq!(
+            encoded(RenderMode::Block, ColorMode::Ansi256),
+            "\x1b[38;5;196m█ \n\x1b[38;5;21m█\x1b[38;5;231m█\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn half_block() {
+        assert_eq!(
+            encoded(RenderMode::HalfBlock, ColorMode::TrueColor),
+            "\x1b[38;2;255;0;0;48;2;0;0;255m▀\x1b[49;38;2;255;255;255m▄\n\x1b[0m"
+        );
+        assert_eq!(encoded(RenderMode::HalfBlock, ColorMode::None), "█▄\n");
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(encoded(RenderMode::Ascii, ColorMode::None), "- \n:@\n");
+    }
+
+    #[test]
+    fn at() {
+        let mut buf = Vec::new();
+        encode_at(
+            &mut buf,
+            &framebuffer(),
+            4,
+            2,
+            RenderMode::Block,
+            ColorMode::Ansi16,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "\x1b[3;5H\x1b[31m█\x1b[4;5H\x1b[34m█\x1b[37m█\x1b[0m"
+        );
+
+        let mut framebuffer = framebuffer();
+        framebuffer.get_mut(0, 0).unwrap().color = None;
+        let mut buf = Vec::new();
+        encode_at(
+            &mut buf,
+            &framebuffer,
+            0,
+            0,
+            RenderMode::Block,
+            ColorMode::None,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "\x1b[1;1H\x1b[2;1H██");
+    }
+}
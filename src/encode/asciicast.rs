@@ -0,0 +1,79 @@
+//! An [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) recorder, which
+//! stores terminal output as newline-delimited JSON.
+
+use std::{fmt::Write as _, io};
+
+/// Returns `text` as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                // Writing to a `String` cannot fail.
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Writes the header and output events of a recording.
+pub struct Recorder<W> {
+    buf: W,
+}
+
+impl<W: io::Write> Recorder<W> {
+    /// Writes the header of a recording of a terminal with `width` columns and `height`
+    /// rows.
+    pub fn new(mut buf: W, width: usize, height: usize) -> io::Result<Self> {
+        writeln!(
+            buf,
+            r#"{{"version": 2, "width": {}, "height": {}, "env": {{"TERM": "xterm-256color"}}}}"#,
+            width, height
+        )?;
+        Ok(Self { buf })
+    }
+
+    /// Records that `output` was written to the terminal `time` seconds into the
+    /// recording.
+    pub fn output(&mut self, time: f64, output: &str) -> io::Result<()> {
+        writeln!(self.buf, "[{:.6}, \"o\", {}]", time, json_string(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, Recorder};
+
+    #[test]
+    fn escapes() {
+        assert_eq!(json_string("a\"b\\c"), r#""a\"b\\c""#);
+        assert_eq!(json_string("\x1b[31m█\n"), r#""\u001b[31m█\n""#);
+    }
+
+    #[test]
+    fn events() {
+        let mut buf = Vec::new();
+        let mut recorder = Recorder::new(&mut buf, 80, 41).unwrap();
+        recorder.output(0.0, "\x1b[2J").unwrap();
+        recorder.output(0.05, "hi").unwrap();
+        let recording = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            recording,
+            concat!(
+                "{\"version\": 2, \"width\": 80, \"height\": 41, ",
+                "\"env\": {\"TERM\": \"xterm-256color\"}}\n",
+                "[0.000000, \"o\", \"\\u001b[2J\"]\n",
+                "[0.050000, \"o\", \"hi\"]\n",
+            )
+        );
+    }
+}
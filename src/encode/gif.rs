@@ -0,0 +1,391 @@
+//! An animated [GIF](https://www.w3.org/Graphics/GIF/spec-gif89a.txt) encoder.
+
+use std::{collections::HashMap, io};
+
+use crate::Image;
+
+/// The most codes that a LZW code table may hold.
+const MAX_CODES: u16 = 1 << 12;
+
+/// Writes packed LZW codes least significant first into sub-blocks of up to 255 bytes.
+struct CodeWriter {
+    blocks: Vec<u8>,
+    block: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl CodeWriter {
+    fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            block: Vec::with_capacity(255),
+            buffer: 0,
+            len: 0,
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8) {
+        self.block.push(byte);
+        if self.block.len() == 255 {
+            self.blocks.push(255);
+            self.blocks.append(&mut self.block);
+        }
+    }
+
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= u32::from(code) << self.len;
+        self.len += size;
+        while self.len >= 8 {
+            self.push_byte(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Flushes the remaining bits and returns the sub-blocks, ending with the block
+    /// terminator.
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.push_byte(self.buffer as u8);
+        }
+        if !self.block.is_empty() {
+            self.blocks.push(self.block.len() as u8);
+            self.blocks.append(&mut self.block);
+        }
+        self.blocks.push(0);
+        self.blocks
+    }
+}
+
+/// Compresses palette `indices` with variable-length-code LZW, starting with codes of
+/// `min_code_size + 1` bits.
+fn lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = CodeWriter::new();
+    let mut table = HashMap::new();
+    let mut next = end + 1;
+    let mut size = min_code_size + 1;
+    writer.write(clear, size);
+
+    let mut indices = indices.iter();
+    let mut prefix = match indices.next() {
+        Some(&index) => u16::from(index),
+        None => {
+            writer.write(end, size);
+            return writer.finish();
+        }
+    };
+    for &index in indices {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, size);
+        if next < MAX_CODES {
+            table.insert((prefix, index), next);
+            next += 1;
+            // Decoders add each code one step behind, so widen once they fill the table.
+            if next > 1 << size && size < 12 {
+                size += 1;
+            }
+        } else {
+            writer.write(clear, size);
+            table.clear();
+            next = end + 1;
+            size = min_code_size + 1;
+        }
+        prefix = index.into();
+    }
+    writer.write(prefix, size);
+    // A decoder adds one more code after the last prefix, which may widen the end code.
+    if next < MAX_CODES && next + 1 > 1 << size && size < 12 {
+        size += 1;
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+/// Returns up to 256 colors representing `colors`, which are weighted by their counts,
+/// by repeatedly splitting the box of colors with the widest channel at its median.
+fn median_cut(colors: Vec<([u8; 3], usize)>) -> Vec<[u8; 3]> {
+    // Returns the channel with the widest range and that range.
+    fn widest(colors: &[([u8; 3], usize)]) -> (usize, u8) {
+        (0..3)
+            .map(|channel| {
+                let (min, max) = colors.iter().fold((u8::MAX, 0), |(min, max), (color, _)| {
+                    (min.min(color[channel]), max.max(color[channel]))
+                });
+                (channel, max.saturating_sub(min))
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap()
+    }
+
+    let mut boxes = vec![colors];
+    while boxes.len() < 256 {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(index, colors)| {
+                let (channel, range) = widest(colors);
+                (index, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range)
+            .map(|(index, channel, _)| (index, channel))
+        else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|(color, _)| color[channel]);
+        let total: usize = colors.iter().map(|(_, count)| count).sum();
+        let mut seen = 0;
+        let median = colors
+            .iter()
+            .position(|(_, count)| {
+                seen += count;
+                seen * 2 >= total
+            })
+            .unwrap()
+            .clamp(0, colors.len() - 2);
+        let upper = colors.split_off(median + 1);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|colors| {
+            let total: usize = colors.iter().map(|(_, count)| count).sum();
+            let mut sum = [0; 3];
+            for (color, count) in colors {
+                for channel in 0..3 {
+                    sum[channel] += usize::from(color[channel]) * count;
+                }
+            }
+            sum.map(|channel| ((channel + total / 2) / total) as u8)
+        })
+        .collect()
+}
+
+/// Returns a palette of up to 256 colors for `pixels` and the index into it of each
+/// pixel. The palette is exact when there are few enough distinct colors.
+fn quantize(pixels: &[[u8; 3]]) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut counts = HashMap::new();
+    for &pixel in pixels {
+        *counts.entry(pixel).or_insert(0) += 1;
+    }
+    let mut colors: Vec<_> = counts.into_iter().collect();
+    // Sort for a deterministic palette regardless of hashing.
+    colors.sort_unstable();
+
+    let palette = if colors.len() <= 256 {
+        colors.iter().map(|&(color, _)| color).collect()
+    } else {
+        median_cut(colors)
+    };
+
+    let distance = |a: [u8; 3], b: [u8; 3]| -> i32 {
+        (0..3)
+            .map(|channel| (i32::from(a[channel]) - i32::from(b[channel])).pow(2))
+            .sum()
+    };
+    let mut nearest = HashMap::new();
+    let indices = pixels
+        .iter()
+        .map(|&pixel| {
+            *nearest.entry(pixel).or_insert_with(|| {
+                (0..palette.len())
+                    .min_by_key(|&index| distance(palette[index], pixel))
+                    .unwrap_or(0) as u8
+            })
+        })
+        .collect();
+    (palette, indices)
+}
+
+/// Writes the frames of a looping animated GIF.
+pub struct Encoder<W> {
+    buf: W,
+    width: u16,
+    height: u16,
+}
+
+impl<W: io::Write> Encoder<W> {
+    /// Writes the header of an animation of `width` by `height` pixels that loops
+    /// forever.
+    pub fn new(mut buf: W, width: usize, height: usize) -> io::Result<Self> {
+        let dimension = |n: usize| {
+            u16::try_from(n).ok().filter(|&n| n > 0).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "invalid GIF dimensions")
+            })
+        };
+        let (width, height) = (dimension(width)?, dimension(height)?);
+
+        buf.write_all(b"GIF89a")?;
+        buf.write_all(&width.to_le_bytes())?;
+        buf.write_all(&height.to_le_bytes())?;
+        // No global color table, background color 0 and square pixels.
+        buf.write_all(&[0, 0, 0])?;
+        // Application extension looping forever.
+        buf.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\0\0\0")?;
+
+        Ok(Self { buf, width, height })
+    }
+
+    /// Writes `image` as the next frame, shown for `delay` hundredths of a second.
+    pub fn write_frame(&mut self, image: &Image, delay: u16) -> io::Result<()> {
+        if (image.width(), image.height()) != (self.width.into(), self.height.into()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "GIF frame does not match the animation's dimensions",
+            ));
+        }
+
+        let pixels: Vec<_> = image.pixels().iter().map(|pixel| pixel.to_rgb8()).collect();
+        let (palette, indices) = quantize(&pixels);
+        // The color table holds 2^(bits) entries, and LZW codes start at 2 bits.
+        let bits = palette.len().next_power_of_two().trailing_zeros().max(1);
+
+        // Graphic control extension with the delay and no transparency.
+        self.buf.write_all(b"\x21\xf9\x04\0")?;
+        self.buf.write_all(&delay.to_le_bytes())?;
+        self.buf.write_all(&[0, 0])?;
+
+        // Image descriptor covering the whole canvas with a local color table.
+        self.buf.write_all(b"\x2c\0\0\0\0")?;
+        self.buf.write_all(&self.width.to_le_bytes())?;
+        self.buf.write_all(&self.height.to_le_bytes())?;
+        self.buf.write_all(&[0x80 | (bits - 1) as u8])?;
+        for index in 0..1 << bits {
+            self.buf
+                .write_all(palette.get(index).unwrap_or(&[0, 0, 0]))?;
+        }
+
+        let min_code_size = bits.max(2);
+        self.buf.write_all(&[min_code_size as u8])?;
+        self.buf.write_all(&lzw(&indices, min_code_size))
+    }
+
+    /// Writes the trailer of the animation and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.buf.write_all(b"\x3b")?;
+        Ok(self.buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lzw, quantize, Encoder};
+    use crate::{Color, Image};
+
+    /// Decodes the sub-blocks of LZW codes written by [`lzw`].
+    fn unlzw(blocks: &[u8], min_code_size: u32) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut rest = blocks;
+        while rest[0] != 0 {
+            let len = rest[0] as usize;
+            data.extend(&rest[1..=len]);
+            rest = &rest[len + 1..];
+        }
+
+        let clear = 1 << min_code_size;
+        let end = clear + 1;
+        let reset = || {
+            (0..clear)
+                .map(|index| vec![index as u8])
+                .collect::<Vec<_>>()
+        };
+        let mut table = reset();
+        let mut size = min_code_size + 1;
+        let mut prev: Option<Vec<u8>> = None;
+        let mut output = Vec::new();
+        let (mut buffer, mut len, mut bytes) = (0u32, 0, data.iter());
+        loop {
+            while len < size {
+                buffer |= u32::from(*bytes.next().unwrap()) << len;
+                len += 8;
+            }
+            let code = (buffer & ((1 << size) - 1)) as usize;
+            buffer >>= size;
+            len -= size;
+
+            if code == clear {
+                table = reset();
+                // The clear and end codes occupy table entries.
+                table.extend([Vec::new(), Vec::new()]);
+                size = min_code_size + 1;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+            let entry = match (table.get(code), &prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) => [prev.as_slice(), &prev[..1]].concat(),
+                (None, None) => panic!("invalid code {}", code),
+            };
+            output.extend(&entry);
+            if let Some(prev) = prev {
+                if table.len() < 4096 {
+                    table.push([prev.as_slice(), &entry[..1]].concat());
+                }
+            }
+            if table.len() == 1 << size && size < 12 {
+                size += 1;
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        for min_code_size in [2, 3, 8] {
+            let alphabet = 1u32 << min_code_size;
+            // Long enough to fill the code table and clear it several times.
+            let indices: Vec<u8> = (0..50_000u32)
+                .map(|i| ((i * 7 + i / 13 + (i * i) % 11) % alphabet) as u8)
+                .collect();
+            for len in [0, 1, 2, 3, 10, 300, indices.len()] {
+                let blocks = lzw(&indices[..len], min_code_size);
+                assert_eq!(unlzw(&blocks, min_code_size), indices[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn quantize_exact_and_reduced() {
+        let (palette, indices) = quantize(&[[0, 0, 0], [255, 0, 0], [0, 0, 0]]);
+        assert_eq!(palette, [[0, 0, 0], [255, 0, 0]]);
+        assert_eq!(indices, [0, 1, 0]);
+
+        let gradient: Vec<_> = (0..1024)
+            .map(|i| [(i / 4) as u8, (i % 256) as u8, 7])
+            .collect();
+        let (palette, indices) = quantize(&gradient);
+        assert_eq!(palette.len(), 256);
+        for (pixel, &index) in gradient.iter().zip(&indices) {
+            let color = palette[index as usize];
+            assert!((0..3).all(|channel| color[channel].abs_diff(pixel[channel]) <= 16));
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let mut encoder = Encoder::new(Vec::new(), 2, 2).unwrap();
+        encoder
+            .write_frame(&Image::new(2, 2, Color::BLUE), 4)
+            .unwrap();
+        assert!(encoder
+            .write_frame(&Image::new(3, 2, Color::BLUE), 4)
+            .is_err());
+        let buf = encoder.finish().unwrap();
+        assert_eq!(buf[..13], *b"GIF89a\x02\0\x02\0\0\0\0");
+        assert_eq!(buf.last(), Some(&0x3b));
+    }
+}
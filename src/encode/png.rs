@@ -0,0 +1,96 @@
+use std::io;
+
+use super::zlib;
+use crate::Image;
+
+/// The bytes that begin every PNG file.
+const SIGNATURE: [u8; 8] = *b"\x89PNG\r\n\x1a\n";
+
+/// Returns the CRC-32 checksum of `data`, as used by PNG chunks.
+fn crc32(data: &[u8]) -> u32 {
+    let table: [u32; 256] = {
+        let mut table = [0; 256];
+        for (n, entry) in table.iter_mut().enumerate() {
+            let mut c = n as u32;
+            for _ in 0..8 {
+                c = if c & 1 == 1 {
+                    0xedb8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+            }
+            *entry = c;
+        }
+        table
+    };
+    !data.iter().fold(!0, |c, &byte| {
+        table[((c ^ u32::from(byte)) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+/// Writes a chunk of `kind` containing `data`.
+fn write_chunk(mut buf: impl io::Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "PNG chunk is too large"))?;
+    buf.write_all(&len.to_be_bytes())?;
+    buf.write_all(kind)?;
+    buf.write_all(data)?;
+    let crc = crc32(&[kind.as_slice(), data].concat());
+    buf.write_all(&crc.to_be_bytes())
+}
+
+/// Writes `image` as an 8-bit RGB PNG.
+pub fn encode(mut buf: impl io::Write, image: &Image) -> io::Result<()> {
+    let dimension = |n: usize| {
+        u32::try_from(n)
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid PNG dimensions"))
+    };
+
+    buf.write_all(&SIGNATURE)?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(dimension(image.width())?.to_be_bytes());
+    header.extend(dimension(image.height())?.to_be_bytes());
+    // 8 bits per channel, truecolor, deflate, adaptive filtering and no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(&mut buf, b"IHDR", &header)?;
+
+    // Each scanline starts with its filter type, which is always none.
+    let mut data = Vec::with_capacity((image.width() * 3 + 1) * image.height());
+    for row in image.pixels().chunks(image.width()) {
+        data.push(0);
+        data.extend(row.iter().flat_map(|pixel| pixel.to_rgb8()));
+    }
+    write_chunk(&mut buf, b"IDAT", &zlib::compress(&data))?;
+
+    write_chunk(&mut buf, b"IEND", &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, encode};
+    use crate::{Color, Image};
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn chunks() {
+        let mut buf = Vec::new();
+        encode(&mut buf, &Image::new(3, 2, Color::RED)).unwrap();
+        assert_eq!(buf[..8], *b"\x89PNG\r\n\x1a\n");
+        assert_eq!(buf[8..16], *b"\0\0\0\x0dIHDR");
+        assert_eq!(buf[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(buf[buf.len() - 12..], *b"\0\0\0\0IEND\xae\x42\x60\x82");
+    }
+
+    #[test]
+    fn empty() {
+        assert!(encode(Vec::new(), &Image::new(0, 4, Color::RED)).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+use std::io;
+
+use crate::Image;
+
+/// Writes `image` as a binary PPM (`P6`) with 8 bits per channel.
+pub fn encode(mut buf: impl io::Write, image: &Image) -> io::Result<()> {
+    write!(buf, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    let data: Vec<u8> = image
+        .pixels()
+        .iter()
+        .flat_map(|pixel| pixel.to_rgb8())
+        .collect();
+    buf.write_all(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+    use crate::{Color, Image};
+
+    #[test]
+    fn header_and_pixels() {
+        let mut image = Image::new(2, 1, Color::BLACK);
+        *image.get_mut(1, 0).unwrap() = Color::YELLOW;
+        let mut buf = Vec::new();
+        encode(&mut buf, &image).unwrap();
+        assert_eq!(buf, b"P6\n2 1\n255\n\0\0\0\xff\xff\0");
+    }
+}
@@ -0,0 +1,42 @@
+use std::io;
+
+use crate::{Color, Framebuffer};
+
+/// Characters of increasing brightness used for cells that were hit.
+const RAMP: &[char] = &['.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Returns a character from [`RAMP`] as bright as the luminance of `color`.
+pub fn glyph(color: Color) -> char {
+    let index = color.luminance().clamp(0.0, 1.0) * (RAMP.len() - 1) as f64;
+    RAMP[index.round() as usize]
+}
+
+/// Writes `framebuffer` as plain text, with a [`glyph`] for each cell that was hit, as
+/// bright as its color blended over black by its coverage, and blanks for the others.
+pub fn encode(mut buf: impl io::Write, framebuffer: &Framebuffer) -> io::Result<()> {
+    for row in framebuffer.rows() {
+        let line: String = row
+            .iter()
+            .map(|cell| cell.color.map_or(' ', |_| glyph(cell.over(Color::BLACK))))
+            .collect();
+        writeln!(buf, "{}", line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+    use crate::{Color, Framebuffer};
+
+    #[test]
+    fn ramp() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.get_mut(0, 0).unwrap().color = Some(Color::WHITE);
+        framebuffer.get_mut(1, 0).unwrap().color = Some(Color::BLACK);
+        framebuffer.get_mut(2, 1).unwrap().color = Some(Color::GREEN);
+        let mut buf = Vec::new();
+        encode(&mut buf, &framebuffer).unwrap();
+        assert_eq!(buf, b"@. \n  #\n");
+    }
+}
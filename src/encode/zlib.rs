@@ -0,0 +1,187 @@
+//! A zlib ([RFC 1950](https://www.rfc-editor.org/rfc/rfc1950)) compressor using
+//! DEFLATE ([RFC 1951](https://www.rfc-editor.org/rfc/rfc1951)) with fixed Huffman codes.
+
+/// The size of the sliding window that matches may refer back into.
+const WINDOW: usize = 32 * 1024;
+
+/// The shortest and longest lengths of a match.
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// The number of bits used to hash the next [`MIN_MATCH`] bytes.
+const HASH_BITS: u32 = 15;
+
+/// The smallest match lengths of each length code from 257, and their extra bits.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The smallest distances of each distance code, and their extra bits.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Writes bits least significant first, as DEFLATE requires.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    /// Writes the `len` lowest bits of `bits`.
+    fn write(&mut self, bits: u32, len: u32) {
+        self.buffer |= bits << self.len;
+        self.len += len;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Writes a Huffman `code` of `len` bits, which is packed most significant first.
+    fn write_code(&mut self, code: u32, len: u32) {
+        self.write(code.reverse_bits() >> (32 - len), len);
+    }
+
+    /// Pads the last byte with zeros and returns the written bytes.
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Writes a literal byte or end-of-block (256) or length (257 to 285) symbol with the
+/// fixed Huffman code.
+fn write_symbol(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => writer.write_code(0b0011_0000 + symbol, 8),
+        144..=255 => writer.write_code(0b1_1001_0000 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0b1100_0000 + symbol - 280, 8),
+    }
+}
+
+/// Writes a match of `len` bytes starting `distance` bytes back.
+fn write_match(writer: &mut BitWriter, len: usize, distance: usize) {
+    let code = LENGTH_BASES.partition_point(|&base| base as usize <= len) - 1;
+    write_symbol(writer, 257 + code as u32);
+    writer.write(
+        (len - LENGTH_BASES[code] as usize) as u32,
+        LENGTH_EXTRA[code].into(),
+    );
+
+    let code = DISTANCE_BASES.partition_point(|&base| base as usize <= distance) - 1;
+    writer.write_code(code as u32, 5);
+    writer.write(
+        (distance - DISTANCE_BASES[code] as usize) as u32,
+        DISTANCE_EXTRA[code].into(),
+    );
+}
+
+/// Returns the hash of the [`MIN_MATCH`] bytes at the start of `bytes`.
+fn hash(bytes: &[u8]) -> usize {
+    let key = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+    (key.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Compresses `data` into a single DEFLATE block, greedily taking the most recent
+/// match found through a hash of the next few bytes.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // BFINAL, then BTYPE 01 for fixed Huffman codes.
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut i = 0;
+    while i < data.len() {
+        let mut len = 0;
+        if i + MIN_MATCH <= data.len() {
+            let hash = hash(&data[i..]);
+            let candidate = head[hash];
+            head[hash] = i;
+            if candidate != usize::MAX && i - candidate <= WINDOW {
+                len = data[candidate..]
+                    .iter()
+                    .zip(&data[i..])
+                    .take(MAX_MATCH)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+            }
+            if len >= MIN_MATCH {
+                write_match(&mut writer, len, i - candidate);
+                // Keep the skipped positions findable by later matches.
+                for j in i + 1..(i + len).min(data.len().saturating_sub(MIN_MATCH - 1)) {
+                    head[self::hash(&data[j..])] = j;
+                }
+                i += len;
+                continue;
+            }
+        }
+        write_symbol(&mut writer, data[i].into());
+        i += 1;
+    }
+
+    write_symbol(&mut writer, 256);
+    writer.finish()
+}
+
+/// Returns the Adler-32 checksum of `data`.
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1, 0);
+    // Sums of up to 5552 bytes cannot overflow before being reduced.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    b << 16 | a
+}
+
+/// Compresses `data` into a zlib stream.
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, default compression level, with the check bits making
+    // the header a multiple of 31.
+    let mut stream = vec![0x78, 0x9c];
+    stream.extend(deflate(data));
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, compress};
+
+    #[test]
+    fn adler() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn compresses_repetition() {
+        let data = [b"shapes".as_slice(); 1000].concat();
+        let stream = compress(&data);
+        assert!(stream.len() < 100);
+        assert_eq!(stream[..2], [0x78, 0x9c]);
+        assert_eq!(stream[stream.len() - 4..], adler32(&data).to_be_bytes());
+    }
+}
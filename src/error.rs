@@ -0,0 +1,63 @@
+use std::{error, fmt, io};
+
+use crate::{cli::ArgsError, scene::SceneError};
+
+/// An error that ends a run of the program.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// A scene could not be loaded.
+    Scene(SceneError),
+    /// The command-line arguments were invalid.
+    Args(ArgsError),
+}
+
+impl Error {
+    /// Returns the status that the program should exit with: 2 for invalid arguments,
+    /// following the convention for usage errors, and 1 otherwise.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Args(_) => 2,
+            Self::Io(_) | Self::Scene(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Scene(error) => write!(f, "{}", error),
+            Self::Args(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Scene(error) => Some(error),
+            Self::Args(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<SceneError> for Error {
+    fn from(error: SceneError) -> Self {
+        Self::Scene(error)
+    }
+}
+
+impl From<ArgsError> for Error {
+    fn from(error: ArgsError) -> Self {
+        Self::Args(error)
+    }
+}
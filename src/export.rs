@@ -0,0 +1,136 @@
+//! Rendering to files.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    encode::{asciicast, gif, png, ppm, text, Format},
+    terminal_frames, terminal_viewport, Camera, Clock, Color, Error, Image, Options, Viewport,
+    Watcher, TERMINAL_SIZE,
+};
+
+/// The size of images in pixels when none is given.
+const IMAGE_SIZE: (usize, usize) = (640, 640);
+
+/// The number of frames per second in files when none is given.
+const FPS: f64 = 20.0;
+
+/// The number of frames of animations in files when none is given.
+const FRAMES: usize = 100;
+
+/// How rendered cells are turned into pixels of an image.
+#[derive(Copy, Clone, Debug)]
+enum Raster {
+    /// Every pixel is rendered on its own.
+    Pixels,
+    /// Every terminal cell is rendered once and drawn as a block of `width` by `height`
+    /// pixels, like a terminal would show it.
+    Cells { width: usize, height: usize },
+}
+
+impl Raster {
+    /// Returns a viewport for an image of `width` by `height` pixels, rounded down to
+    /// a whole number of cells, and how many pixels each of its cells should cover.
+    fn viewport(self, width: usize, height: usize) -> (Viewport, (usize, usize)) {
+        let (cell_width, cell_height) = match self {
+            Self::Pixels => (1, 1),
+            Self::Cells { width, height } => (width, height),
+        };
+        let (columns, rows) = (width / cell_width, height / cell_height);
+        // Keep the vertical field of view of the terminal and widen it to fit the image.
+        let aspect = (columns * cell_width) as f64 / (rows * cell_height) as f64;
+        let viewport = Viewport::new(columns, rows, Camera::new(0.5, aspect, 1.0));
+        (viewport, (cell_width, cell_height))
+    }
+
+    /// Renders the scene in `options`, `time` seconds after the first frame, into an
+    /// image of about `width` by `height` pixels.
+    fn render(self, options: &Options, width: usize, height: usize, time: f64) -> Image {
+        let (viewport, (cell_width, cell_height)) = self.viewport(width, height);
+        let image = options.rasterize(&viewport, time).to_image(Color::BLACK);
+        match self {
+            Self::Pixels => image,
+            Self::Cells { .. } => image.upscale(cell_width, cell_height),
+        }
+    }
+}
+
+/// Renders the scene in `options` to `path`, in the [`Format`] of its extension.
+pub(crate) fn export(path: &Path, options: &Options) -> Result<(), Error> {
+    let format = Format::from_path(path);
+    let (width, height) = options.size.unwrap_or(match format {
+        Format::Ppm | Format::Png | Format::Gif => IMAGE_SIZE,
+        Format::Asciicast | Format::Text | Format::Ansi => TERMINAL_SIZE,
+    });
+    let raster = match options.cell {
+        Some((width, height)) => Raster::Cells { width, height },
+        None => Raster::Pixels,
+    };
+    let fps = options.fps.unwrap_or(FPS);
+
+    let file = File::create(path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("could not create {}: {}", path.display(), error),
+        )
+    })?;
+    let mut file = BufWriter::new(file);
+    match format {
+        Format::Ppm => ppm::encode(&mut file, &raster.render(options, width, height, 0.0))?,
+        Format::Png => png::encode(&mut file, &raster.render(options, width, height, 0.0))?,
+        Format::Gif => {
+            let (viewport, (cell_width, cell_height)) = raster.viewport(width, height);
+            let mut encoder = gif::Encoder::new(
+                &mut file,
+                viewport.width * cell_width,
+                viewport.height * cell_height,
+            )?;
+            // GIF delays are in hundredths of a second, so round each frame's end time
+            // to keep the animation from drifting.
+            let centiseconds = |frame: usize| (frame as f64 * 100.0 / fps).round() as u64;
+            for frame in 0.. {
+                let time = frame as f64 / fps;
+                if options.is_over(frame, time, Some(FRAMES)) {
+                    break;
+                }
+                let image = raster.render(options, width, height, time);
+                let delay = centiseconds(frame + 1) - centiseconds(frame);
+                encoder.write_frame(&image, delay.try_into().unwrap_or(u16::MAX))?;
+            }
+            encoder.finish()?;
+        }
+        Format::Asciicast => {
+            // The cursor is left on the line below the frame.
+            let mut recorder = asciicast::Recorder::new(&mut file, width, height + 1)?;
+            terminal_frames(
+                options,
+                Watcher::new(options)?,
+                Clock::fixed(fps),
+                Some(FRAMES),
+                |time, output| {
+                    // Record newlines as a terminal's line discipline would output them.
+                    let output = String::from_utf8_lossy(output).replace('\n', "\r\n");
+                    recorder.output(time, &output)
+                },
+            )?;
+        }
+        Format::Text => {
+            let viewport = terminal_viewport(width, height, Default::default());
+            text::encode(&mut file, &options.rasterize(&viewport, 0.0))?;
+        }
+        Format::Ansi => {
+            terminal_frames(
+                options,
+                Watcher::new(options)?,
+                Clock::fixed(fps),
+                Some(FRAMES),
+                |_, output| file.write_all(output),
+            )?;
+        }
+    }
+    file.flush()?;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::{Animate, Color};
+
+/// How fog thickens with distance from the camera.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Falloff {
+    /// None at `start` and thickening evenly to hide everything by `end`.
+    Linear { start: f64, end: f64 },
+    /// Hiding `density` of what is left with every unit of distance, thinning out but
+    /// never quite hiding everything, like real fog.
+    Exponential { density: f64 },
+}
+
+/// Fog that blends the colors of objects toward its own color the farther away they
+/// are, applied by [`HittableExt::fog`](crate::HittableExt::fog), so that far objects
+/// stand out less than near ones.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Fog {
+    /// The color of the fog, which objects fade into.
+    pub color: Color,
+    /// How the fog thickens with distance.
+    pub falloff: Falloff,
+}
+
+impl Fog {
+    /// Returns how much of something `distance` away the fog hides, from 0 to 1.
+    pub fn amount(&self, distance: f64) -> f64 {
+        let amount = match self.falloff {
+            Falloff::Linear { start, end } => match end > start {
+                true => (distance - start) / (end - start),
+                false => f64::from(distance >= start),
+            },
+            Falloff::Exponential { density } => 1.0 - (-density * distance).exp(),
+        };
+        amount.clamp(0.0, 1.0)
+    }
+
+    /// Returns `color`, seen from `distance` away through this fog.
+    pub fn apply(&self, color: Color, distance: f64) -> Color {
+        color.lerp(self.color, self.amount(distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Falloff, Fog};
+    use crate::{cube, util::check_about, Color, Hittable, HittableExt, Ray, Translation, Vector};
+
+    #[test]
+    fn amount() {
+        let linear = Fog {
+            color: Color::WHITE,
+            falloff: Falloff::Linear {
+                start: 2.0,
+                end: 6.0,
+            },
+        };
+        check_about(linear.amount(1.0), 0.0);
+        check_about(linear.amount(3.0), 0.25);
+        check_about(linear.amount(f64::INFINITY), 1.0);
+        check_about(linear.apply(Color::BLACK, 4.0).0, 0.5);
+
+        let exponential = Fog {
+            color: Color::WHITE,
+            falloff: Falloff::Exponential { density: 0.5 },
+        };
+        check_about(exponential.amount(0.0), 0.0);
+        check_about(exponential.amount(2.0), 1.0 - (-1.0f64).exp());
+        check_about(exponential.amount(f64::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn fogged() {
+        let fog = Fog {
+            color: Color::BLUE,
+            falloff: Falloff::Linear {
+                start: 0.0,
+                end: 5.0,
+            },
+        };
+        let scene = cube()
+            .colorize(Color::RED)
+            .transform(Translation(Vector(0.0, 3.0, 0.0)))
+            .fog(fog);
+        // The fog goes by distance, however long the direction of the ray is.
+        let hit = scene
+            .hit(Ray {
+                origin: Vector::ZERO,
+                direction: Vector(0.0, 0.5, 0.0),
+            })
+            .unwrap();
+        check_about(hit.t, 5.0);
+        check_about(hit.color.0, 0.5);
+        check_about(hit.color.2, 0.5);
+    }
+}
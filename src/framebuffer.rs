@@ -0,0 +1,298 @@
+use crate::{Color, Hit, Image, ObjectId};
+
+/// A single cell of a [`Framebuffer`].
+///
+/// Outside of this crate, cells start out as [`Cell::EMPTY`] and have their fields set.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Cell {
+    /// The color of this cell, or [`None`] if nothing was hit.
+    pub color: Option<Color>,
+    /// The distance from the camera to the closest [`Hit`], or infinity if nothing was
+    /// hit.
+    pub depth: f64,
+    /// The object that was struck, if it has been identified.
+    pub object: Option<ObjectId>,
+    /// The fraction of this cell's samples that missed, from 0 to 1, which leaves a
+    /// cell fully covered by its color unless set otherwise.
+    pub missed: f64,
+}
+
+impl Cell {
+    /// A cell where nothing was hit.
+    pub const EMPTY: Self = Self {
+        color: None,
+        depth: f64::INFINITY,
+        object: None,
+        missed: 0.0,
+    };
+
+    /// Returns a cell averaging the colors of the samples that hit something, with the
+    /// depth and object of the closest one.
+    pub fn from_samples(samples: impl IntoIterator<Item = Option<Hit>>) -> Self {
+        let (mut sum, mut hits, mut count) = (Color::BLACK, 0, 0);
+        let mut closest: Option<Hit> = None;
+        for sample in samples {
+            count += 1;
+            if let Some(hit) = sample {
+                sum += hit.color;
+                hits += 1;
+                if closest.is_none_or(|closest| hit.t < closest.t) {
+                    closest = Some(hit);
+                }
+            }
+        }
+        match closest {
+            Some(closest) => Self {
+                color: Some(sum / hits as f64),
+                depth: closest.t,
+                object: closest.object,
+                missed: (count - hits) as f64 / count as f64,
+            },
+            None => Self::EMPTY,
+        }
+    }
+
+    /// Returns the fraction of this cell that was hit, from 0 to 1.
+    pub fn coverage(&self) -> f64 {
+        match self.color {
+            Some(_) => 1.0 - self.missed,
+            None => 0.0,
+        }
+    }
+
+    /// Returns the color of this cell if at least half of it was hit, for drawing it as
+    /// a solid block.
+    pub fn solid(&self) -> Option<Color> {
+        self.color.filter(|_| self.coverage() >= 0.5)
+    }
+
+    /// Returns the color of this cell blended over `background` by its coverage.
+    pub fn over(&self, background: Color) -> Color {
+        match self.color {
+            Some(color) => color * (1.0 - self.missed) + background * self.missed,
+            None => background,
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl From<Option<Hit>> for Cell {
+    fn from(hit: Option<Hit>) -> Self {
+        match hit {
+            Some(hit) => Self {
+                color: Some(hit.color),
+                depth: hit.t,
+                object: hit.object,
+                missed: 0.0,
+            },
+            None => Self::EMPTY,
+        }
+    }
+}
+
+/// A rendered grid of [`Cell`]s, stored row by row from the top-left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Framebuffer {
+    /// Returns a framebuffer of `width` by `height` [empty](Cell::EMPTY) cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::EMPTY; width * height],
+        }
+    }
+
+    /// Returns the number of columns of this framebuffer.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows of this framebuffer.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cell at column `x` and row `y`, counting from the top-left.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Returns a mutable reference to the cell at column `x` and row `y`, counting from
+    /// the top-left.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Returns an iterator over the rows of this framebuffer, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        // `max(1)` keeps `chunks` from panicking on a framebuffer with no columns.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Returns an iterator over mutable references to the rows of this framebuffer, from
+    /// top to bottom.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Cell]> {
+        self.cells.chunks_mut(self.width.max(1))
+    }
+
+    /// Draws the cells of `other` where something was hit over the cells of this
+    /// framebuffer, with its top-left cell at column `x` and row `y`, leaving the rest
+    /// of this framebuffer untouched.
+    ///
+    /// Cells falling outside of this framebuffer are clipped.
+    pub fn overlay(&mut self, other: &Framebuffer, x: usize, y: usize) {
+        for (j, row) in other.rows().enumerate() {
+            for (i, cell) in row.iter().enumerate() {
+                if cell.color.is_none() {
+                    continue;
+                }
+                if let Some(target) = self.get_mut(x + i, y + j) {
+                    *target = *cell;
+                }
+            }
+        }
+    }
+
+    /// Dims the cells of this framebuffer by how far away what they show is, from not at
+    /// all up to `near` to by `amount`, from 0 to 1, from `far` on, to make depth easier
+    /// to see in the few shades of a terminal.
+    ///
+    /// The range is fixed rather than fitted to what is in view, so that an object keeps
+    /// its shade as others move around it. Cells showing only the background are left as
+    /// they are.
+    pub fn depth_cue(&mut self, near: f64, far: f64, amount: f64) {
+        for cell in &mut self.cells {
+            let distance = match cell.depth {
+                depth if !depth.is_finite() => continue,
+                depth if far > near => ((depth - near) / (far - near)).clamp(0.0, 1.0),
+                depth => f64::from(depth >= far),
+            };
+            if let Some(color) = &mut cell.color {
+                *color = *color * (1.0 - amount * distance);
+            }
+        }
+    }
+
+    /// Returns an image with a pixel for each cell, blended over `background` by how
+    /// much of the cell was hit.
+    pub fn to_image(&self, background: Color) -> Image {
+        let mut image = Image::new(self.width, self.height, background);
+        for (y, row) in self.rows().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(pixel) = image.get_mut(x, y) {
+                    *pixel = cell.over(background);
+                }
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cell, Framebuffer};
+    use crate::{
+        cube, util::check_about, Camera, Color, Hit, HittableExt, Translation, Vector, Viewport,
+    };
+
+    #[test]
+    fn get() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.get_mut(2, 1).unwrap().color = Some(Color::RED);
+        assert!(framebuffer.get(3, 0).is_none());
+        assert!(framebuffer.get(0, 2).is_none());
+        assert_eq!(framebuffer.get(2, 1).unwrap().color, Some(Color::RED));
+        let rows: Vec<_> = framebuffer.rows().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], [Cell::EMPTY; 3]);
+        assert_eq!(rows[1][2].color, Some(Color::RED));
+    }
+
+    #[test]
+    fn overlay() {
+        let mut host = Framebuffer::new(3, 3);
+        for x in 0..3 {
+            host.get_mut(x, 2).unwrap().color = Some(Color::BLUE);
+        }
+        let mut widget = Framebuffer::new(2, 2);
+        widget.get_mut(0, 0).unwrap().color = Some(Color::RED);
+        widget.get_mut(1, 1).unwrap().color = Some(Color::GREEN);
+        host.overlay(&widget, 2, 1);
+        assert_eq!(host.get(2, 1).unwrap().color, Some(Color::RED));
+        assert_eq!(host.get(2, 2).unwrap().color, Some(Color::BLUE));
+        assert_eq!(host.get(1, 1).unwrap().color, None);
+    }
+
+    #[test]
+    fn depth_cue() {
+        // White cubes seen through a viewport whose center shows the front of one 2.5
+        // away, and whose top-left corner shows another at `corner` along its ray, or
+        // the background if that is behind the camera.
+        let viewport = Viewport::new(3, 3, Camera::new(0.5, 1.0, 1.0));
+        let cube_at = |y: f64| {
+            cube().colorize(Color::WHITE).transform(Translation(Vector(
+                -y * 2.0 / 3.0,
+                y,
+                y * 2.0 / 3.0,
+            )))
+        };
+        let frame = |corner: f64| {
+            let scene = cube().colorize(Color::WHITE);
+            let scene = scene.transform(Translation(Vector(0.0, 3.0, 0.0)));
+            let mut framebuffer =
+                viewport.rasterize(scene.and(cube_at(corner)).background(Color::WHITE));
+            framebuffer.depth_cue(2.0, 4.0, 0.5);
+            framebuffer
+        };
+        let framebuffer = frame(-5.0);
+        // A quarter of the way through the range, the cube is dimmed by a quarter of the
+        // amount.
+        check_about(framebuffer.get(1, 1).unwrap().color.unwrap().0, 0.875);
+        // The background is not dimmed.
+        assert_eq!(framebuffer.get(0, 0).unwrap().color, Some(Color::WHITE));
+        // The front of the cube in the corner is 2 ahead, but farther away along the
+        // slanting ray to it.
+        let near = frame(2.5);
+        let distance = 4.0 * Vector(-1.0 / 3.0, 0.5, 1.0 / 3.0).len();
+        let corner = near.get(0, 0).unwrap();
+        check_about(corner.depth, distance);
+        check_about(corner.color.unwrap().0, 1.0 - 0.5 * (distance - 2.0) / 2.0);
+        // An object that stands still keeps its shade whatever else comes and goes.
+        let far = frame(10.0);
+        check_about(far.get(0, 0).unwrap().color.unwrap().0, 0.5);
+        assert_eq!(near.get(1, 1), framebuffer.get(1, 1));
+        assert_eq!(far.get(1, 1), framebuffer.get(1, 1));
+        // Empty cells stay empty.
+        let mut empty = Framebuffer::new(2, 2);
+        empty.depth_cue(2.0, 6.0, 1.0);
+        assert_eq!(empty, Framebuffer::new(2, 2));
+    }
+
+    #[test]
+    fn samples() {
+        let cell = Cell::from_samples([
+            Some(Hit::new(Color::RED, 2.0)),
+            None,
+            Some(Hit::new(Color::BLUE, 1.0)),
+            None,
+        ]);
+        assert_eq!(cell.color, Some(Color(0.5, 0.0, 0.5)));
+        check_about(cell.depth, 1.0);
+        check_about(cell.coverage(), 0.5);
+        assert_eq!(cell.solid(), cell.color);
+        assert_eq!(cell.over(Color::WHITE), Color(0.75, 0.5, 0.75));
+        assert_eq!(Cell::from_samples([None, None]), Cell::EMPTY);
+    }
+}
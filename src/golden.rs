@@ -0,0 +1,212 @@
+//! Golden-file regression tests that render named scenes at fixed times.
+//!
+//! Each case is rendered to plain text and PPM and compared against the files of the
+//! same name in `tests/golden`, allowing for the few cells that can come out slightly
+//! differently on another platform or toolchain, whose `sin`, `cos` and `sqrt` may round
+//! differently. Running the tests with the `SHAPES_BLESS` environment
+//! variable set overwrites those files with the current outputs instead.
+
+use std::{env, fs, path::PathBuf};
+
+use crate::{
+    cube, decode,
+    encode::{ppm, text},
+    scene, Camera, Color, Description, Hittable, HittableExt, Sampling, Translation, Vector,
+    Viewport,
+};
+
+/// A scene rendered at a fixed time.
+struct Case {
+    name: &'static str,
+    scene: fn(f64) -> Box<dyn Hittable + Sync>,
+    time: f64,
+    sampling: Sampling,
+}
+
+const CASES: &[Case] = &[
+    Case {
+        name: "demo-0",
+        scene: |time| Box::new(scene::demo(time)),
+        time: 0.0,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "demo-1.7",
+        scene: |time| Box::new(scene::demo(time)),
+        time: 1.7,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "demo-11.3",
+        scene: |time| Box::new(scene::demo(time)),
+        time: 11.3,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "cube-0.6",
+        scene: |time| Box::new(scene::in_view(scene::spinning_cube(time))),
+        time: 0.6,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "torus-4.2",
+        scene: |time| Box::new(scene::in_view(scene::tumbling_torus(time))),
+        time: 4.2,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "flight-2.5",
+        scene: |time| Box::new(scene::flight(time)),
+        time: 2.5,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "glass-1",
+        scene: |time| {
+            let description: Description = include_str!("../docs/glass.scene").parse().unwrap();
+            Box::new(description.at(time))
+        },
+        time: 1.0,
+        sampling: Sampling::Center,
+    },
+    // A cube behind the camera must not be visible.
+    Case {
+        name: "behind",
+        scene: |_| Box::new(cube().transform(Translation(Vector(0.0, -3.0, 0.0)))),
+        time: 0.0,
+        sampling: Sampling::Center,
+    },
+    Case {
+        name: "demo-1.7-grid",
+        scene: |time| Box::new(scene::demo(time)),
+        time: 1.7,
+        sampling: Sampling::Grid {
+            columns: 3,
+            rows: 3,
+        },
+    },
+];
+
+/// How far apart, out of 255, a channel of a pixel can be from its golden value without
+/// the pixel differing.
+const CHANNEL_TOLERANCE: f64 = 2.0;
+
+/// How many cells of an output can differ from its golden file before the output does.
+const MAX_DIFFERENCES: usize = 8;
+
+/// Returns how many characters of the text `actual` differ from those of `expected`, or
+/// [`None`] if their lines are not the same lengths.
+fn text_differences(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    let (expected, actual) = (
+        String::from_utf8_lossy(expected),
+        String::from_utf8_lossy(actual),
+    );
+    if expected.lines().count() != actual.lines().count() {
+        return None;
+    }
+    let mut differences = 0;
+    for (expected, actual) in expected.lines().zip(actual.lines()) {
+        if expected.chars().count() != actual.chars().count() {
+            return None;
+        }
+        differences += expected
+            .chars()
+            .zip(actual.chars())
+            .filter(|(expected, actual)| expected != actual)
+            .count();
+    }
+    Some(differences)
+}
+
+/// Returns how many pixels of the PPM image `actual` are further than
+/// [`CHANNEL_TOLERANCE`] from those of `expected`, or [`None`] if the images are not
+/// the same size or cannot be read.
+fn image_differences(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    let expected = decode::ppm::decode(expected).ok()?;
+    let actual = decode::ppm::decode(actual).ok()?;
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        return None;
+    }
+    let close = |expected: f64, actual: f64| (expected - actual).abs() * 255.0 <= CHANNEL_TOLERANCE;
+    let differences = expected.pixels().iter().zip(actual.pixels());
+    Some(
+        differences
+            .filter(|(expected, actual)| {
+                !(close(expected.0, actual.0)
+                    && close(expected.1, actual.1)
+                    && close(expected.2, actual.2))
+            })
+            .count(),
+    )
+}
+
+/// Compares `actual` against the golden file `name` by `differences`, or overwrites it
+/// when blessing.
+fn check(
+    name: &str,
+    actual: &[u8],
+    differences: fn(&[u8], &[u8]) -> Option<usize>,
+) -> Result<(), String> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if env::var_os("SHAPES_BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return Ok(());
+    }
+    match fs::read(&path) {
+        Ok(expected) => match differences(&expected, actual) {
+            Some(count) if count <= MAX_DIFFERENCES => Ok(()),
+            Some(count) => Err(format!(
+                "{} differs from its golden file in {} cells",
+                name, count
+            )),
+            None => Err(format!("{} differs from its golden file in size", name)),
+        },
+        Err(error) => Err(format!("{} could not be read: {}", path.display(), error)),
+    }
+}
+
+#[test]
+fn tolerance() {
+    assert_eq!(text_differences(b"ab\ncd\n", b"ab\ncd\n"), Some(0));
+    assert_eq!(text_differences(b"ab\ncd\n", b"ax\ncd\n"), Some(1));
+    assert_eq!(text_differences(b"ab\ncd\n", b"ab\ncde\n"), None);
+    let image = |pixels: &[u8]| [b"P6\n2 1\n255\n", pixels].concat();
+    let expected = image(&[10, 20, 30, 40, 50, 60]);
+    assert_eq!(
+        image_differences(&expected, &image(&[12, 19, 30, 40, 50, 60])),
+        Some(0)
+    );
+    assert_eq!(
+        image_differences(&expected, &image(&[10, 20, 30, 40, 50, 70])),
+        Some(1)
+    );
+    assert_eq!(image_differences(&expected, b"P6\n1 1\n255\n\0\0\0"), None);
+}
+
+#[test]
+fn golden() {
+    let mut viewport = Viewport::new(80, 40, Camera::new(0.5, 1.0, 1.0));
+
+    let mut failures = Vec::new();
+    for case in CASES {
+        viewport.sampling = case.sampling;
+        let framebuffer = viewport.rasterize((case.scene)(case.time));
+
+        let mut txt = Vec::new();
+        text::encode(&mut txt, &framebuffer).unwrap();
+        let mut image = Vec::new();
+        ppm::encode(&mut image, &framebuffer.to_image(Color::BLACK)).unwrap();
+
+        failures.extend(check(&format!("{}.txt", case.name), &txt, text_differences).err());
+        failures.extend(check(&format!("{}.ppm", case.name), &image, image_differences).err());
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\nrerun with SHAPES_BLESS=1 to accept the new outputs",
+        failures.join("\n")
+    );
+}
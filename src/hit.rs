@@ -1,33 +1,112 @@
-use crate::{Color, Ray, Transformation, TransformationExt};
+use crate::{
+    Background, Color, Fog, Lighting, Lit, Material, PathTraced, Ray, Texture, Transformation,
+    TransformationExt, Vector,
+};
 
 /// An object in space that can be hit by a [`Ray`] to possibly produce a [`Hit`].
-pub(crate) trait Hittable {
+pub trait Hittable {
     /// Attempts to hit this object given an `incidence` ray of light.
     fn hit(&self, incidence: Ray) -> Option<Hit>;
 }
 
-impl<T: Hittable> Hittable for &T {
+impl<T: ?Sized + Hittable> Hittable for &T {
     fn hit(&self, incidence: Ray) -> Option<Hit> {
         T::hit(*self, incidence)
     }
 }
 
-impl<T: Hittable> Hittable for &mut T {
+impl<T: ?Sized + Hittable> Hittable for &mut T {
     fn hit(&self, incidence: Ray) -> Option<Hit> {
         T::hit(*self, incidence)
     }
 }
 
+impl<T: ?Sized + Hittable> Hittable for Box<T> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        T::hit(self, incidence)
+    }
+}
+
+/// A group of objects, which is hit where the closest of them is hit.
+impl<H: Hittable> Hittable for [H] {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.iter()
+            .filter_map(|hittable| hittable.hit(incidence))
+            .reduce(|closest, hit| if hit.t < closest.t { hit } else { closest })
+    }
+}
+
+impl<H: Hittable> Hittable for Vec<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.as_slice().hit(incidence)
+    }
+}
+
 /// Produced when a [`Ray`] hits a [`Hittable`].
+///
+/// More information may be added to hits over time, so outside of this crate they are
+/// created with [`Hit::new`].
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct Hit {
-    pub(crate) color: Color,
-    /// The distance [at](Ray::at) the incidence ray where the [`Hittable`] was struck.
-    pub(crate) t: f64,
+#[non_exhaustive]
+pub struct Hit {
+    /// The color of the [`Hittable`] where it was struck.
+    pub color: Color,
+    /// The distance [at](Ray::at) the incidence ray where the [`Hittable`] was struck,
+    /// or infinity for its [background](HittableExt::background).
+    pub t: f64,
+    /// The object that was struck, if it has been identified.
+    pub object: Option<ObjectId>,
+    /// Where the surface was struck, as coordinates across it from 0 to 1 that
+    /// [`Texture`]s are mapped by.
+    pub uv: (f64, f64),
+    /// Where the [`Hittable`] was struck, in its own space before any transformations.
+    pub point: Vector,
+    /// The direction straight out of the surface where it was struck, with a length of
+    /// 1, or zero if the surface has no direction.
+    pub normal: Vector,
+    /// What the surface is made of, for lighting.
+    pub material: Material,
+}
+
+impl Hit {
+    /// Returns a hit of `color` at distance `t`, on no identified object, at the
+    /// origin of a matte surface with no direction.
+    pub fn new(color: Color, t: f64) -> Self {
+        Self {
+            color,
+            t,
+            object: None,
+            uv: (0.0, 0.0),
+            point: Vector::ZERO,
+            normal: Vector::ZERO,
+            material: Material::MATTE,
+        }
+    }
+}
+
+/// Identifies an object within a scene.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ObjectId(pub u32);
+
+impl ObjectId {
+    /// Returns the id of the object called `name`, which is the same for the same name
+    /// every time.
+    pub const fn named(name: &str) -> Self {
+        // The 32-bit FNV-1a hash of the name.
+        let bytes = name.as_bytes();
+        let mut hash: u32 = 0x811c_9dc5;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u32;
+            hash = hash.wrapping_mul(0x0100_0193);
+            i += 1;
+        }
+        Self(hash)
+    }
 }
 
 /// Extension utilities implemented for all [`Hittable`].
-pub(crate) trait HittableExt: Hittable {
+pub trait HittableExt: Hittable {
     /// Returns a [`Hittable`] that performs a [`Transformation`] before and after
     /// this one.
     fn transform<T: Transformation>(self, transformation: T) -> Transformed<Self, T>
@@ -52,6 +131,95 @@ pub(crate) trait HittableExt: Hittable {
         }
     }
 
+    /// Returns a [`Hittable`] colored by a [`Texture`] wherever it is hit.
+    fn texture<T: Texture>(self, texture: T) -> Textured<Self, T>
+    where
+        Self: Sized,
+    {
+        Textured {
+            hittable: self,
+            texture,
+        }
+    }
+
+    /// Returns a [`Hittable`] made of `material`.
+    fn material(self, material: Material) -> Materialize<Self>
+    where
+        Self: Sized,
+    {
+        Materialize {
+            hittable: self,
+            material,
+        }
+    }
+
+    /// Returns a [`Hittable`] that shows `background` wherever rays miss this one, as
+    /// hits on no object at an infinite distance, with no surface direction.
+    fn background<B: Background>(self, background: B) -> WithBackground<Self, B>
+    where
+        Self: Sized,
+    {
+        WithBackground {
+            hittable: self,
+            background,
+        }
+    }
+
+    /// Returns this [`Hittable`], as the whole of a scene seen from the origin, seen
+    /// through `fog`, in front of its [background](HittableExt::background), which is
+    /// left as it is.
+    fn fog(self, fog: Fog) -> Fogged<Self>
+    where
+        Self: Sized,
+    {
+        Fogged {
+            hittable: self,
+            fog,
+        }
+    }
+
+    /// Returns this [`Hittable`], as the whole of a scene seen from the origin, shaded
+    /// by `lighting` with light bouncing between its objects as their [`Material`]s
+    /// say.
+    fn lit(self, lighting: Lighting) -> Lit<Self>
+    where
+        Self: Sized,
+    {
+        Lit::new(self, lighting)
+    }
+
+    /// Returns this [`Hittable`], as the whole of a scene seen from the origin, colored
+    /// by one random path of light through it from each ray that hits it, lit by
+    /// `lighting` and by any objects whose [`Material`]s give off light.
+    ///
+    /// Each ray follows the same path every time, so an image that converges on the
+    /// light of the scene averages many slightly different rays, as a
+    /// [`PathTracer`](crate::PathTracer) does.
+    fn path_traced(self, lighting: Lighting) -> PathTraced<Self>
+    where
+        Self: Sized,
+    {
+        PathTraced::new(self, lighting)
+    }
+
+    /// Returns a [`Hittable`] whose [`Hit`]s are on the object identified by `id`,
+    /// replacing any id given to parts of this one.
+    fn tag(self, id: ObjectId) -> Tagged<Self>
+    where
+        Self: Sized,
+    {
+        Tagged { hittable: self, id }
+    }
+
+    /// Returns a [`Hittable`] whose [`Hit`]s are on the object called `name`, with the id
+    /// of [`ObjectId::named`].
+    fn named(self, name: &str) -> Tagged<Self>
+    where
+        Self: Sized,
+    {
+        self.tag(ObjectId::named(name))
+    }
+
     /// Returns a [`Hittable`] that returns the closest [`Hit`] of `self` and `other`.
     fn and<O: Hittable>(self, other: O) -> And<Self, O>
     where
@@ -68,37 +236,139 @@ impl<T: ?Sized + Hittable> HittableExt for T {}
 
 /// Created by [`HittableExt::transform`].
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct Transformed<H, T> {
+pub struct Transformed<H, T> {
     hittable: H,
     transformation_inverse: T,
 }
 
 impl<H: Hittable, T: Transformation> Hittable for Transformed<H, T> {
     fn hit(&self, incidence: Ray) -> Option<Hit> {
-        self.hittable
-            .hit(self.transformation_inverse.transform_ray(incidence))
+        let hit = self
+            .hittable
+            .hit(self.transformation_inverse.transform_ray(incidence))?;
+        if hit.normal == Vector::ZERO {
+            return Some(hit);
+        }
+        // Normals are carried out by the transpose of the inverse, which keeps them
+        // square to the surface when it is stretched.
+        let inverse = &self.transformation_inverse;
+        let origin = inverse.transform(Vector::ZERO);
+        let column = |axis: Vector| inverse.transform(axis) - origin;
+        let normal = Vector(
+            column(Vector(1.0, 0.0, 0.0)).dot(hit.normal),
+            column(Vector(0.0, 1.0, 0.0)).dot(hit.normal),
+            column(Vector(0.0, 0.0, 1.0)).dot(hit.normal),
+        );
+        Some(Hit {
+            normal: normal.normalized(),
+            ..hit
+        })
     }
 }
 
 /// Created by [`HittableExt::colorize`].
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct Colorize<H> {
+pub struct Colorize<H> {
     hittable: H,
     color: Color,
 }
 
 impl<H: Hittable> Hittable for Colorize<H> {
     fn hit(&self, incidence: Ray) -> Option<Hit> {
-        self.hittable.hit(incidence).map(|Hit { t, .. }| Hit {
+        self.hittable.hit(incidence).map(|hit| Hit {
             color: self.color,
-            t,
+            ..hit
+        })
+    }
+}
+
+/// Created by [`HittableExt::texture`].
+#[derive(Copy, Clone, Debug)]
+pub struct Textured<H, T> {
+    hittable: H,
+    texture: T,
+}
+
+impl<H: Hittable, T: Texture> Hittable for Textured<H, T> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.hittable.hit(incidence).map(|hit| Hit {
+            color: self.texture.color(&hit),
+            ..hit
+        })
+    }
+}
+
+/// Created by [`HittableExt::material`].
+#[derive(Copy, Clone, Debug)]
+pub struct Materialize<H> {
+    hittable: H,
+    material: Material,
+}
+
+impl<H: Hittable> Hittable for Materialize<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.hittable.hit(incidence).map(|hit| Hit {
+            material: self.material,
+            ..hit
+        })
+    }
+}
+
+/// Created by [`HittableExt::background`].
+#[derive(Copy, Clone, Debug)]
+pub struct WithBackground<H, B> {
+    hittable: H,
+    background: B,
+}
+
+impl<H: Hittable, B: Background> Hittable for WithBackground<H, B> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        let background = || Hit::new(self.background.color(incidence.direction), f64::INFINITY);
+        Some(self.hittable.hit(incidence).unwrap_or_else(background))
+    }
+}
+
+/// Created by [`HittableExt::fog`].
+#[derive(Copy, Clone, Debug)]
+pub struct Fogged<H> {
+    hittable: H,
+    fog: Fog,
+}
+
+impl<H: Hittable> Hittable for Fogged<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        let hit = self.hittable.hit(incidence)?;
+        // A background is no distance away that the fog could be measured over.
+        if !hit.t.is_finite() {
+            return Some(hit);
+        }
+        let distance = hit.t * incidence.direction.len();
+        Some(Hit {
+            color: self.fog.apply(hit.color, distance),
+            ..hit
+        })
+    }
+}
+
+/// Created by [`HittableExt::tag`] and [`HittableExt::named`].
+#[derive(Copy, Clone, Debug)]
+pub struct Tagged<H> {
+    hittable: H,
+    id: ObjectId,
+}
+
+impl<H: Hittable> Hittable for Tagged<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.hittable.hit(incidence).map(|hit| Hit {
+            object: Some(self.id),
+            ..hit
         })
     }
 }
 
 /// Created by [`HittableExt::and`].
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct And<T, U> {
+pub struct And<T, U> {
     first: T,
     second: U,
 }
@@ -112,3 +382,107 @@ impl<T: Hittable, U: Hittable> Hittable for And<T, U> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{HittableExt, ObjectId};
+    use crate::{
+        cube, util::check_about, Color, Hittable, Lighting, Material, Matrix, Ray, Torus,
+        Translation, Vector,
+    };
+
+    fn check_vector(lhs: Vector, rhs: Vector) {
+        check_about((lhs - rhs).len(), 0.0);
+    }
+
+    #[test]
+    fn normals() {
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        let hit = cube()
+            .transform(Translation(Vector(0.0, 3.0, 0.0)))
+            .hit(incidence)
+            .unwrap();
+        check_vector(hit.normal, Vector(0.0, -1.0, 0.0));
+
+        // A cube turned by 45 degrees and then squashed along x, hit on a side that the
+        // squashing turns to face more along x.
+        let hit = cube()
+            .transform(Matrix::rotation_z(std::f64::consts::FRAC_PI_4))
+            .transform(Matrix::scale(Vector(0.5, 1.0, 1.0)))
+            .transform(Translation(Vector(0.0, 3.0, 0.0)))
+            .hit(Ray {
+                origin: Vector(0.1, 0.0, 0.0),
+                direction: Vector(0.0, 1.0, 0.0),
+            })
+            .unwrap();
+        check_vector(hit.normal, Vector(2.0, -1.0, 0.0) / 5f64.sqrt());
+
+        let hit = Torus {
+            radius_major: 1.0,
+            radius_minor: 0.25,
+        }
+        .transform(Translation(Vector(0.0, 2.0, 0.0)))
+        .hit(Ray {
+            origin: Vector(-1.0, 0.0, 0.0),
+            direction: Vector(0.0, 1.0, 0.0),
+        })
+        .unwrap();
+        check_vector(hit.normal, Vector(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn tag() {
+        assert_eq!(ObjectId::named(""), ObjectId(0x811c_9dc5));
+        assert_eq!(ObjectId::named("cube"), ObjectId::named("cube"));
+        assert_ne!(ObjectId::named("cube"), ObjectId::named("torus"));
+
+        let near = cube()
+            .named("near")
+            .transform(Translation(Vector(0.0, 2.0, 0.0)));
+        let far = cube()
+            .named("far")
+            .transform(Translation(Vector(0.0, 4.0, 0.0)));
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        let hit = far.and(near).hit(incidence).unwrap();
+        assert_eq!(hit.object, Some(ObjectId::named("near")));
+        let hit = far.and(near).tag(ObjectId(7)).hit(incidence).unwrap();
+        assert_eq!(hit.object, Some(ObjectId(7)));
+        assert_eq!(cube().hit(incidence).unwrap().object, None);
+    }
+
+    #[test]
+    fn background() {
+        let scene = cube()
+            .transform(Translation(Vector(0.0, 3.0, 0.0)))
+            .background(|direction: Vector| match direction.z() > 0.0 {
+                true => Color::BLUE,
+                false => Color::BLACK,
+            });
+        let ray = |direction| Ray {
+            origin: Vector::ZERO,
+            direction,
+        };
+        let hit = scene.hit(ray(Vector(0.0, 1.0, 0.0))).unwrap();
+        assert_eq!(hit.color, Color::RED);
+        let hit = scene.hit(ray(Vector(0.0, 0.0, 1.0))).unwrap();
+        assert_eq!(hit.color, Color::BLUE);
+        assert_eq!(hit.t, f64::INFINITY);
+        assert_eq!(hit.object, None);
+        // The background is reflected like anything else.
+        let mirror = cube()
+            .material(Material::MIRROR)
+            .transform(Matrix::rotation_x(-std::f64::consts::FRAC_PI_4))
+            .transform(Translation(Vector(0.0, 3.0, 0.0)));
+        let lit = mirror
+            .background(Color::GREEN)
+            .lit(Lighting::new(Vec::new()));
+        let hit = lit.hit(ray(Vector(0.0, 1.0, 0.0))).unwrap();
+        assert!(hit.color.1 > 0.8 && hit.color.0 < 0.2, "{:?}", hit.color);
+    }
+}
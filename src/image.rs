@@ -0,0 +1,88 @@
+use std::iter;
+
+use crate::Color;
+
+/// A raster image of [`Color`] pixels, stored row by row from the top-left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Returns an image of `width` by `height` pixels, all of which are `fill`.
+    pub fn new(width: usize, height: usize, fill: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// Returns the number of columns of this image.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows of this image.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixels of this image, row by row from the top-left.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns this image with each pixel repeated into a block of `width` by `height`
+    /// pixels.
+    pub fn upscale(&self, width: usize, height: usize) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * width * height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let scaled: Vec<_> = row
+                .iter()
+                .flat_map(|&pixel| iter::repeat_n(pixel, width))
+                .collect();
+            for _ in 0..height {
+                pixels.extend(&scaled);
+            }
+        }
+        Self {
+            width: self.width * width,
+            height: self.height * height,
+            pixels,
+        }
+    }
+
+    /// Returns the pixel at column `x` and row `y`, counting from the top-left.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Color> {
+        (x < self.width && y < self.height).then(|| &self.pixels[y * self.width + x])
+    }
+
+    /// Returns a mutable reference to the pixel at column `x` and row `y`, counting
+    /// from the top-left.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Color> {
+        (x < self.width && y < self.height).then(|| &mut self.pixels[y * self.width + x])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Image;
+    use crate::Color;
+
+    #[test]
+    fn upscale() {
+        let mut image = Image::new(2, 1, Color::BLACK);
+        *image.get_mut(1, 0).unwrap() = Color::WHITE;
+        let scaled = image.upscale(2, 3);
+        assert_eq!((scaled.width(), scaled.height()), (4, 3));
+        for row in scaled.pixels().chunks(4) {
+            assert_eq!(
+                row,
+                [Color::BLACK, Color::BLACK, Color::WHITE, Color::WHITE]
+            );
+        }
+    }
+}
@@ -0,0 +1,668 @@
+//! The interactive mode, in which the view is orbited with the keyboard and objects are
+//! picked with the mouse.
+
+use std::{
+    f64::consts::{PI, TAU},
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    encode::ansi, scene, terminal_viewport, write_overlay, Color, Error, Follow, Framebuffer,
+    ObjectId, Options, Orbit, Scene, Spring, Watcher, TERMINAL_SIZE,
+};
+
+/// How far the view is orbited by each press of an arrow key, in radians.
+const ORBIT_STEP: f64 = PI / 24.0;
+
+/// How much the distance to the scene is multiplied by each press of `+`.
+const ZOOM_STEP: f64 = 0.9;
+
+/// The spring that the view follows the orbit steered by the keys on.
+const COAST: Spring = Spring {
+    frequency: 1.5,
+    damping: 1.0,
+};
+
+/// Escape sequences that turn on reporting of mouse buttons in the SGR 1006 encoding.
+const MOUSE_ON: &str = "\x1b[?1000h\x1b[?1006h";
+
+/// Escape sequences that turn off what [`MOUSE_ON`] turned on.
+const MOUSE_OFF: &str = "\x1b[?1006l\x1b[?1000l";
+
+/// The longest the parameters of a mouse report can be, past which an unfinished one is
+/// given up on rather than waited for.
+const MAX_MOUSE_PARAMETERS: usize = 32;
+
+/// A key press or mouse event that controls the interactive mode.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Input {
+    Up,
+    Down,
+    Left,
+    Right,
+    ZoomIn,
+    ZoomOut,
+    Pause,
+    Quit,
+    /// A click of the left mouse button on a cell, counting from 0 at the top-left.
+    Click {
+        column: usize,
+        line: usize,
+    },
+}
+
+/// Parses a mouse event reported in the SGR 1006 encoding, `ESC [ < button ; column ;
+/// line M`, from the part of `input` after `ESC [ <`, returning it and the rest of the
+/// input if it is complete.
+fn parse_mouse(input: &[u8]) -> Option<(Option<Input>, &[u8])> {
+    let end = input
+        .iter()
+        .position(|&byte| byte == b'M' || byte == b'm')?;
+    let parameters = std::str::from_utf8(&input[..end]).ok()?;
+    let mut parameters = parameters.split(';').map(str::parse::<usize>);
+    let (Some(Ok(button)), Some(Ok(column)), Some(Ok(line))) =
+        (parameters.next(), parameters.next(), parameters.next())
+    else {
+        return None;
+    };
+    // Releases end in `m`. Only presses of the left button and the scroll wheel matter.
+    let event = match (button, input[end]) {
+        (0, b'M') => Some(Input::Click {
+            column: column.saturating_sub(1),
+            line: line.saturating_sub(1),
+        }),
+        (64, b'M') => Some(Input::ZoomIn),
+        (65, b'M') => Some(Input::ZoomOut),
+        _ => None,
+    };
+    Some((event, &input[end + 1..]))
+}
+
+/// An orbit that coasts after another on springs, settling where it stops.
+struct Coast {
+    yaw: Follow<f64>,
+    pitch: Follow<f64>,
+    distance: Follow<f64>,
+}
+
+impl Coast {
+    /// Returns an orbit that is at rest at `orbit`.
+    fn new(orbit: Orbit) -> Self {
+        Self {
+            yaw: Follow::new(orbit.yaw, COAST),
+            pitch: Follow::new(orbit.pitch, COAST),
+            distance: Follow::new(orbit.distance, COAST),
+        }
+    }
+
+    /// Moves on by `time` seconds after `orbit`, returning the orbit that has been
+    /// reached.
+    fn update(&mut self, orbit: Orbit, time: f64) -> Orbit {
+        // The yaw wraps around, so it is followed the shorter way around.
+        let yaw = self.yaw.value;
+        self.yaw.target = yaw + (orbit.yaw - yaw + PI).rem_euclid(TAU) - PI;
+        self.pitch.target = orbit.pitch;
+        self.distance.target = orbit.distance;
+        for follow in [&mut self.yaw, &mut self.pitch, &mut self.distance] {
+            follow.update(time);
+        }
+        Orbit {
+            yaw: self.yaw.value,
+            pitch: self.pitch.value,
+            distance: self.distance.value,
+            ..orbit
+        }
+    }
+}
+
+/// Returns whether `input` is the start of an escape sequence that the terminal has not
+/// finished sending, such as one split between reads.
+fn is_incomplete(input: &[u8]) -> bool {
+    match input {
+        [b'\x1b'] | [b'\x1b', b'[' | b'O'] => true,
+        [b'\x1b', b'[', b'<', parameters @ ..] => {
+            parameters.len() < MAX_MOUSE_PARAMETERS
+                && parameters
+                    .iter()
+                    .all(|&byte| byte.is_ascii_digit() || byte == b';')
+        }
+        _ => false,
+    }
+}
+
+/// Returns the keys pressed and mouse events in `input` read from a terminal in raw
+/// mode, ignoring any other bytes, along with the unfinished escape sequence at its end
+/// that the next read should continue.
+fn parse_input(mut input: &[u8]) -> (Vec<Input>, &[u8]) {
+    let mut events = Vec::new();
+    while let [byte, rest @ ..] = input {
+        if is_incomplete(input) {
+            break;
+        }
+        input = rest;
+        let event = match (byte, rest) {
+            (b'\x1b', [b'[', b'<', mouse @ ..]) => match parse_mouse(mouse) {
+                Some((event, rest)) => {
+                    input = rest;
+                    match event {
+                        Some(event) => event,
+                        None => continue,
+                    }
+                }
+                None => continue,
+            },
+            // Arrow keys are sent as `ESC [ A` to `ESC [ D`, or with `O` instead of `[` in
+            // application cursor mode.
+            (b'\x1b', [b'[' | b'O', arrow @ b'A'..=b'D', rest @ ..]) => {
+                input = rest;
+                match arrow {
+                    b'A' => Input::Up,
+                    b'B' => Input::Down,
+                    b'C' => Input::Right,
+                    _ => Input::Left,
+                }
+            }
+            (b'w' | b'W', _) => Input::Up,
+            (b's' | b'S', _) => Input::Down,
+            (b'a' | b'A', _) => Input::Left,
+            (b'd' | b'D', _) => Input::Right,
+            (b'+' | b'=', _) => Input::ZoomIn,
+            (b'-' | b'_', _) => Input::ZoomOut,
+            (b' ', _) => Input::Pause,
+            // Ctrl-C is read as a byte, since signals are off in raw mode.
+            (b'q' | b'Q' | b'\x03', _) => Input::Quit,
+            _ => continue,
+        };
+        events.push(event);
+    }
+    (events, input)
+}
+
+/// Returns the object drawn on `line` at `column` of the terminal showing
+/// `framebuffer` with `rows_per_line` rows on each line.
+fn pick(
+    framebuffer: &Framebuffer,
+    rows_per_line: usize,
+    column: usize,
+    line: usize,
+) -> Option<ObjectId> {
+    (line * rows_per_line..(line + 1) * rows_per_line)
+        .filter_map(|row| framebuffer.get(column, row))
+        .find_map(|cell| cell.object)
+}
+
+/// Returns the name of the object identified by `id`, if it is one of the named
+/// objects of `scene`.
+fn name(scene: &Scene, id: ObjectId) -> Option<&str> {
+    let mut names: Box<dyn Iterator<Item = &str>> = match scene {
+        Scene::Described(description) => Box::new(description.names()),
+        _ => Box::new(scene::NAMES.iter().copied()),
+    };
+    names.find(|&name| ObjectId::named(name) == id)
+}
+
+/// Runs the interactive mode in the terminal, until `q` is pressed or the frame or
+/// duration limit of `options` is reached.
+///
+/// Clicking an object selects it, highlighting it and showing its name, and clicking
+/// nothing clears the selection.
+#[cfg(unix)]
+pub(crate) fn run(options: &Options) -> Result<(), Error> {
+    // The scene file is loaded before the terminal is taken over, so that a scene that
+    // cannot be read at all fails the run rather than showing nothing.
+    let watcher = Watcher::new(options)?;
+    let terminal = Terminal::take_over()?;
+    let result = frames(options, watcher, io::stdout().lock(), |timeout| {
+        terminal.raw_mode.read(timeout)
+    });
+    drop(terminal);
+    Ok(result?)
+}
+
+/// The terminal as the interactive mode takes it over, with input in raw mode, the cursor
+/// hidden and mouse reporting on, all of which is undone when this is dropped, even if a
+/// frame panics.
+#[cfg(unix)]
+struct Terminal {
+    raw_mode: crate::terminal::RawMode,
+}
+
+#[cfg(unix)]
+impl Terminal {
+    /// Takes over the terminal, failing if standard input is not one.
+    fn take_over() -> io::Result<Self> {
+        let raw_mode = crate::terminal::RawMode::enable().map_err(|error| {
+            io::Error::new(
+                error.kind(),
+                format!("interactive mode needs a terminal: {}", error),
+            )
+        })?;
+        let terminal = Self { raw_mode };
+        // Hide the cursor while drawing.
+        write!(io::stdout(), "\x1b[?25l\x1b[2J{}", MOUSE_ON)?;
+        Ok(terminal)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // Raw mode is turned off after this, when `raw_mode` is dropped. Failing to write
+        // leaves nothing else to restore.
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[0m\x1b[?25h{}", MOUSE_OFF);
+        let _ = stdout.flush();
+    }
+}
+
+/// Fails, since the interactive mode needs a Unix terminal.
+#[cfg(not(unix))]
+pub(crate) fn run(_options: &Options) -> Result<(), Error> {
+    Err(Error::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "interactive mode is only supported on Unix",
+    )))
+}
+
+/// Renders frames of the scene of `watcher` with `options` to `stdout` as they are
+/// orbited by the keys and picked by the clicks from `read`, which waits up to a timeout
+/// for input.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn frames(
+    options: &Options,
+    mut watcher: Watcher,
+    mut stdout: impl Write,
+    mut read: impl FnMut(Duration) -> io::Result<Vec<u8>>,
+) -> io::Result<()> {
+    let (width, height) = options.size.unwrap_or(TERMINAL_SIZE);
+    let viewport = terminal_viewport(width, height, options.render_mode);
+    let frame_duration = options
+        .fps
+        .map_or(Duration::ZERO, |fps| Duration::from_secs_f64(1.0 / fps));
+
+    let mut tracer = options.path_tracer(&viewport);
+    // The view and time of the samples the path tracer has taken.
+    let mut traced = None;
+    let mut orbit = Orbit::new(watcher.scene().center());
+    let mut coast = Coast::new(orbit);
+    let mut paused = false;
+    let mut selected = None;
+    // The time into the animation, which stands still while paused.
+    let mut time = 0.0;
+    let start = Instant::now();
+    let mut last = start;
+    let mut elapsed = 0.0;
+    let mut output = Vec::new();
+    // Input left over from the last read, which ended partway through an escape sequence.
+    let mut pending = Vec::new();
+    for frame in 0.. {
+        // The duration is counted in real time, so that it ends even while paused.
+        if options.is_over(frame, start.elapsed().as_secs_f64(), None) {
+            break;
+        }
+        let reloaded = watcher.poll();
+        let center = watcher.scene().center();
+        if reloaded && center != orbit.target {
+            // A scene centered somewhere else is orbited around its new center, from the
+            // distance its camera is at, but still from the same direction.
+            orbit = Orbit {
+                target: center,
+                distance: center.len(),
+                ..orbit
+            };
+            coast = Coast::new(orbit);
+        }
+        let view = coast.update(orbit, elapsed);
+        let mut framebuffer = match &mut tracer {
+            // Samples add up for as long as the view and the animation stand still.
+            Some(tracer) => {
+                if reloaded || traced != Some((view, time)) {
+                    tracer.reset();
+                    traced = Some((view, time));
+                }
+                let scene = view.view(watcher.scene().path_traced(options.time + time));
+                options.refine(tracer, scene);
+                tracer.framebuffer()
+            }
+            None => {
+                let scene = view.view(watcher.scene().at(options.time + time));
+                options.rasterize_scene(&viewport, scene)
+            }
+        };
+        // Zooming in and out moves the range of depth cueing with the camera.
+        let zoom = view.distance - center.len();
+        let range = watcher.scene().depth_range();
+        options.depth_cue(
+            &mut framebuffer,
+            range.map(|(near, far)| (near + zoom, far + zoom)),
+        );
+        for row in framebuffer.rows_mut() {
+            for cell in row
+                .iter_mut()
+                .filter(|cell| selected.is_some() && cell.object == selected)
+            {
+                cell.color = cell.color.map(|color| (color + Color::WHITE) / 2.0);
+            }
+        }
+        output.clear();
+        output.extend(b"\x1b[H");
+        ansi::encode(
+            &mut output,
+            &framebuffer,
+            options.render_mode,
+            options.color_mode,
+        )?;
+        if paused {
+            write!(output, "paused  ")?;
+        }
+        if let (Some(tracer), Some(samples)) = (&tracer, options.path_samples) {
+            write!(output, "{}/{} samples  ", tracer.samples(), samples)?;
+        }
+        if let Some(id) = selected {
+            write!(
+                output,
+                "selected {}  ",
+                name(watcher.scene(), id).unwrap_or("object")
+            )?;
+        }
+        write!(
+            output,
+            "arrows/WASD orbit, +/- zoom, space pause, click select, q quit\x1b[K"
+        )?;
+        if let Some(error) = watcher.error() {
+            write_overlay(&mut output, &error.to_string(), width)?;
+        }
+        stdout.write_all(&output)?;
+        stdout.flush()?;
+
+        // Input is handled as it comes in until the next frame is due.
+        let deadline = last + frame_duration;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            pending.extend(read(timeout)?);
+            let (events, rest) = parse_input(&pending);
+            let parsed = pending.len() - rest.len();
+            for event in events {
+                match event {
+                    Input::Up => orbit.rotate(0.0, ORBIT_STEP),
+                    Input::Down => orbit.rotate(0.0, -ORBIT_STEP),
+                    Input::Left => orbit.rotate(-ORBIT_STEP, 0.0),
+                    Input::Right => orbit.rotate(ORBIT_STEP, 0.0),
+                    Input::ZoomIn => orbit.zoom(ZOOM_STEP),
+                    Input::ZoomOut => orbit.zoom(1.0 / ZOOM_STEP),
+                    Input::Pause => paused = !paused,
+                    Input::Click { column, line } => {
+                        selected = pick(
+                            &framebuffer,
+                            options.render_mode.rows_per_line(),
+                            column,
+                            line,
+                        );
+                    }
+                    Input::Quit => {
+                        writeln!(stdout)?;
+                        return Ok(());
+                    }
+                }
+            }
+            pending.drain(..parsed);
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+
+        let now = Instant::now();
+        elapsed = (now - last).as_secs_f64();
+        if !paused {
+            time += elapsed;
+        }
+        last = now;
+    }
+    writeln!(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        env, fs, process,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use super::{frames, parse_input, pick, Coast, Input, ORBIT_STEP};
+    use crate::{
+        encode::ansi, terminal_viewport, util::check_about, Description, Framebuffer, ObjectId,
+        Options, Orbit, Scene, Vector, Watcher,
+    };
+
+    #[test]
+    fn input() {
+        assert_eq!(
+            parse_input(b"\x1b[A\x1bOBad+-x \x1b[Zq").0,
+            [
+                Input::Up,
+                Input::Down,
+                Input::Left,
+                Input::Right,
+                Input::ZoomIn,
+                Input::ZoomOut,
+                Input::Pause,
+                Input::Quit,
+            ]
+        );
+        assert_eq!(parse_input(b"\x1bq\x03").0, [Input::Quit, Input::Quit]);
+        assert_eq!(
+            parse_input(b"\x1b[<0;12;5M\x1b[<0;12;5m\x1b[<2;1;1M\x1b[<65;3;3Mq\x1b[<0;1"),
+            (
+                vec![
+                    Input::Click {
+                        column: 11,
+                        line: 4
+                    },
+                    Input::ZoomOut,
+                    Input::Quit,
+                ],
+                &b"\x1b[<0;1"[..]
+            )
+        );
+    }
+
+    #[test]
+    fn split_input() {
+        // Escape sequences split between reads are left for the next read to finish.
+        assert_eq!(parse_input(b"d\x1b"), (vec![Input::Right], &b"\x1b"[..]));
+        assert_eq!(parse_input(b"\x1b["), (vec![], &b"\x1b["[..]));
+        assert_eq!(parse_input(b"\x1b[A"), (vec![Input::Up], &b""[..]));
+        assert_eq!(parse_input(b"\x1b[<0;12"), (vec![], &b"\x1b[<0;12"[..]));
+        assert_eq!(parse_input(b"\x1b[<0;12;5Ma").0.len(), 2);
+
+        let options = Options {
+            size: Some((8, 4)),
+            ..Options::default()
+        };
+        let reads = Cell::new(0);
+        let mut output = Vec::new();
+        frames(
+            &options,
+            Watcher::new(&options).unwrap(),
+            &mut output,
+            |_: Duration| {
+                reads.set(reads.get() + 1);
+                Ok(match reads.get() {
+                    1 => b"\x1b".to_vec(),
+                    2 => b"[<0;5".to_vec(),
+                    3 => b";3M".to_vec(),
+                    _ => b"q".to_vec(),
+                })
+            },
+        )
+        .unwrap();
+        // The click in the middle, sent in three parts, selects the cube.
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("selected"));
+    }
+
+    #[test]
+    fn retargets() {
+        let path = env::temp_dir().join(format!("shapes-retarget-{}.scene", process::id()));
+        fs::write(&path, "cube\n").unwrap();
+        let options = Options {
+            size: Some((40, 20)),
+            scene_file: Some(path.clone()),
+            ..Options::default()
+        };
+        let reads = Cell::new(0);
+        let mut output = Vec::new();
+        frames(
+            &options,
+            Watcher::new(&options).unwrap(),
+            &mut output,
+            |_: Duration| {
+                reads.set(reads.get() + 1);
+                Ok(match reads.get() {
+                    1 => {
+                        // The camera of the scene moves back as the view turns.
+                        fs::write(&path, "camera distance 6\ncube\n").unwrap();
+                        let file = fs::File::options().write(true).open(&path).unwrap();
+                        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+                        b"dddddd".to_vec()
+                    }
+                    _ => b"q".to_vec(),
+                })
+            },
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let last = output.rsplit("\x1b[H").next().unwrap();
+
+        // The view turned around the new center of the scene, from its new distance.
+        let scene = Scene::Described(Arc::new(Description::load(&path).unwrap()));
+        let orbit = Orbit {
+            yaw: 6.0 * ORBIT_STEP,
+            ..Orbit::new(scene.center())
+        };
+        let viewport = terminal_viewport(40, 20, options.render_mode);
+        let framebuffer = options.rasterize_scene(&viewport, orbit.view(scene.at(0.0)));
+        let mut expected = Vec::new();
+        ansi::encode(
+            &mut expected,
+            &framebuffer,
+            options.render_mode,
+            options.color_mode,
+        )
+        .unwrap();
+        assert!(last.starts_with(&*String::from_utf8(expected).unwrap()));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn duration() {
+        // The duration runs out in real time, even while the animation is paused.
+        let options = Options {
+            size: Some((8, 4)),
+            duration: Some(0.05),
+            ..Options::default()
+        };
+        let reads = Cell::new(0);
+        let mut output = Vec::new();
+        frames(
+            &options,
+            Watcher::new(&options).unwrap(),
+            &mut output,
+            |_: Duration| {
+                reads.set(reads.get() + 1);
+                Ok(match reads.get() {
+                    1 => b" ".to_vec(),
+                    _ => Vec::new(),
+                })
+            },
+        )
+        .unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("paused"));
+    }
+
+    #[test]
+    fn picks() {
+        let mut framebuffer = Framebuffer::new(2, 4);
+        framebuffer.get_mut(1, 3).unwrap().object = Some(ObjectId(3));
+        assert_eq!(pick(&framebuffer, 2, 1, 1), Some(ObjectId(3)));
+        assert_eq!(pick(&framebuffer, 2, 0, 1), None);
+        assert_eq!(pick(&framebuffer, 1, 1, 3), Some(ObjectId(3)));
+        assert_eq!(pick(&framebuffer, 1, 5, 9), None);
+    }
+
+    #[test]
+    fn coasts() {
+        let mut orbit = Orbit::new(Vector(0.0, 3.0, 0.0));
+        let mut coast = Coast::new(orbit);
+        // Turning left wraps the yaw around, but the view turns the short way.
+        orbit.rotate(-0.2, 0.3);
+        let view = coast.update(orbit, 0.1);
+        assert!(-0.2 < view.yaw && view.yaw < 0.0);
+        assert!(0.0 < view.pitch && view.pitch < 0.3);
+        check_about(view.distance, 3.0);
+
+        let view = coast.update(orbit, 10.0);
+        check_about(view.yaw, -0.2);
+        check_about(view.pitch, 0.3);
+    }
+
+    #[test]
+    fn quits() {
+        let options = Options {
+            size: Some((8, 4)),
+            ..Options::default()
+        };
+        let reads = Cell::new(0);
+        let mut output = Vec::new();
+        frames(
+            &options,
+            Watcher::new(&options).unwrap(),
+            &mut output,
+            |_: Duration| {
+                reads.set(reads.get() + 1);
+                Ok(if reads.get() == 3 {
+                    b"d q".to_vec()
+                } else {
+                    Vec::new()
+                })
+            },
+        )
+        .unwrap();
+        assert_eq!(reads.get(), 3);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("\x1b[H").count(), 3);
+        assert_eq!(output.matches("paused").count(), 0);
+    }
+
+    #[test]
+    fn refines() {
+        // Path traced, samples add up while paused, up to the number asked for.
+        let options = Options {
+            size: Some((8, 4)),
+            path_samples: Some(2),
+            ..Options::default()
+        };
+        let reads = Cell::new(0);
+        let mut output = Vec::new();
+        frames(
+            &options,
+            Watcher::new(&options).unwrap(),
+            &mut output,
+            |_: Duration| {
+                reads.set(reads.get() + 1);
+                Ok(match reads.get() {
+                    1 => b" ".to_vec(),
+                    4 => b"q".to_vec(),
+                    _ => Vec::new(),
+                })
+            },
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("1/2 samples").count(), 1);
+        assert_eq!(output.matches("2/2 samples").count(), 3);
+    }
+}
@@ -3,90 +3,197 @@
 //! # Shapes
 //!
 //! Simple 3D objects for your terminal.
+//!
+//! Besides the `shapes` program, this crate can be used to build scenes out of
+//! [`Hittable`] objects and render them into a [`Framebuffer`], which the [`encode`]
+//! module turns into terminal output or images.
+//!
+//! ```
+//! use shapes::prelude::*;
+//!
+//! let scene = cube()
+//!     .colorize(Color::CYAN)
+//!     .transform(Matrix::rotation_z(0.5))
+//!     .transform(Translation(Vector(0.0, 3.0, 0.0)));
+//! let viewport = Viewport::new(40, 20, Camera::new(0.5, 1.0, 1.0));
+//! let framebuffer = viewport.rasterize(&scene);
+//!
+//! let mut output = Vec::new();
+//! encode::ansi::encode(&mut output, &framebuffer, RenderMode::Block, ColorMode::Ansi16)?;
+//! assert!(framebuffer.get(20, 10).unwrap().color.is_some());
+//! # Ok::<(), std::io::Error>(())
+//! ```
 
+mod animation;
+mod background;
+pub mod cli;
+mod clock;
 mod color;
 mod cube;
+pub mod decode;
+mod description;
 mod easing;
+pub mod encode;
+mod error;
+mod export;
+mod fog;
+mod framebuffer;
+#[cfg(test)]
+mod golden;
 mod hit;
+mod image;
+mod interactive;
+mod light;
+mod material;
 mod matrix;
+mod noise;
+mod orbit;
+pub mod prelude;
+mod random;
 mod ray;
+mod scene;
+mod spline;
+mod spring;
+#[cfg(unix)]
+mod terminal;
+mod texture;
 mod torus;
+mod trace;
 mod transform;
 mod translate;
 mod util;
 mod vector;
 mod view;
+mod watch;
+mod widget;
 
-use std::{
-    convert::Infallible,
-    f64::consts::TAU,
-    io::{self, Write},
-    time::Instant,
-};
+use std::io::{self, Write};
 
-use self::{
+pub use self::{
+    animation::{Animate, Keyframe, Repeat, Track},
+    background::{Background, Environment, Sky, Starfield},
+    cli::Options,
     color::Color,
     cube::cube,
-    easing::ease_sin_in_out,
-    hit::{Hit, Hittable, HittableExt},
+    description::Description,
+    easing::{Easing, EasingCurve, EasingError},
+    encode::ansi::{ColorMode, RenderMode},
+    error::Error,
+    fog::{Falloff, Fog},
+    framebuffer::{Cell, Framebuffer},
+    hit::{
+        And, Colorize, Fogged, Hit, Hittable, HittableExt, Materialize, ObjectId, Tagged, Textured,
+        Transformed, WithBackground,
+    },
+    image::Image,
+    light::{Light, Lighting, Lit, PathTraced},
+    material::Material,
     matrix::Matrix,
+    noise::GradientNoise,
+    orbit::Orbit,
     ray::Ray,
+    scene::{Scene, SceneError},
+    spline::{Placement, Spline},
+    spring::{Follow, Spring},
+    texture::{
+        Checker, Filter, Gradient, ImageTexture, Marble, Noise, Stripes, Texture, Wood, Wrap,
+    },
     torus::Torus,
+    trace::PathTracer,
     transform::{Transformation, TransformationExt},
     translate::Translation,
     vector::Vector,
-    view::{Camera, Viewport},
+    view::{Camera, Sampling, Viewport},
+    widget::Widget,
 };
+use self::{clock::Clock, encode::ansi, watch::Watcher};
+
+/// The size of the terminal in cells when none is given.
+const TERMINAL_SIZE: (usize, usize) = (80, 40);
 
-/// Runs the program.
-pub fn run() -> io::Error {
-    match try_run() {
-        Ok(never) => match never {},
-        Err(error) => error,
+/// Runs the program with `options`, until its frame or duration limit is reached.
+pub fn run(options: &Options) -> Result<(), Error> {
+    match &options.output {
+        Some(path) => export::export(path, &options.load_scene()?)?,
+        None if options.interactive => interactive::run(options)?,
+        None => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            let clock = options.fps.map_or_else(Clock::real_time, Clock::paced);
+            // The scene file is loaded before anything is drawn, so that a scene that
+            // cannot be read at all fails the run rather than showing nothing.
+            let watcher = Watcher::new(options)?;
+            terminal_frames(options, watcher, clock, None, |_, output| {
+                stdout.write_all(output)?;
+                stdout.flush()
+            })?;
+        }
     }
+    Ok(())
 }
 
-pub fn try_run() -> Result<Infallible, io::Error> {
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-
-    let viewport = Viewport {
-        camera: Camera {
-            focal_len: 0.5,
-            width: 1.0,
-            height: 1.0,
-        },
-        width: 80,
-        height: 40,
-    };
-
-    let start = Instant::now();
-    let phase = |dur| (start.elapsed().as_millis() % dur) as f64 / dur as f64;
-
-    write!(stdout, "\x1b[2J")?;
+/// Returns a viewport of `width` by `height` terminal cells, which are about twice as
+/// tall as they are wide, with as many rows as `render_mode` draws in that height.
+fn terminal_viewport(width: usize, height: usize, render_mode: RenderMode) -> Viewport {
+    let camera = Camera::new(0.5, width as f64 / (2 * height) as f64, 1.0);
+    Viewport::new(width, height * render_mode.rows_per_line(), camera)
+}
 
-    loop {
-        write!(stdout, "\x1b[H")?;
+/// Writes `message` over the top-left of a terminal `width` columns wide in reverse
+/// video, leaving the cursor where it was.
+fn write_overlay(output: &mut Vec<u8>, message: &str, width: usize) -> io::Result<()> {
+    let message: String = message.chars().take(width.saturating_sub(2)).collect();
+    write!(output, "\x1b7\x1b[H\x1b[0;7m {} \x1b[0m\x1b8", message)
+}
 
-        let cube = cube()
-            .transform(Matrix::scale(Vector(1.3, 1.3, 1.3)))
-            .transform(Matrix::rotation_z(ease_sin_in_out(phase(2500)) * TAU))
-            .transform(Matrix::rotation_x(0.7));
+/// Renders frames of the scene of `watcher` with `options` as they are shown in a
+/// terminal, until the run [is over](Options::is_over), passing the time of each frame
+/// from `clock` and the ANSI escape sequences drawing it to `show`.
+fn terminal_frames(
+    options: &Options,
+    mut watcher: Watcher,
+    clock: Clock,
+    frames: Option<usize>,
+    mut show: impl FnMut(f64, &[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    let (width, height) = options.size.unwrap_or(TERMINAL_SIZE);
+    let viewport = terminal_viewport(width, height, options.render_mode);
 
-        let torus = Torus {
-            radius_major: 1.5,
-            radius_minor: 0.3,
+    let mut tracer = options.path_tracer(&viewport);
+    let mut output = Vec::from(*b"\x1b[2J");
+    for frame in 0.. {
+        let time = clock.frame_time(frame);
+        if options.is_over(frame, time, frames) {
+            break;
         }
-        .transform(Matrix::rotation_x(phase(6000) * TAU))
-        .transform(Matrix::rotation_y(phase(29000) * TAU))
-        .transform(Matrix::rotation_z(-phase(14000) * TAU));
-
-        let scene = cube
-            .and(torus)
-            .transform(Translation(Vector(0.0, 3.0, 0.0)));
-
-        viewport.render(&mut stdout, scene)?;
-
-        stdout.flush()?;
+        let reloaded = watcher.poll();
+        let mut framebuffer = match &mut tracer {
+            // Path traced, the animation stands still while the image refines.
+            Some(tracer) => {
+                if reloaded {
+                    tracer.reset();
+                }
+                options.refine(tracer, watcher.scene().path_traced(options.time));
+                tracer.framebuffer()
+            }
+            None => {
+                let scene = watcher.scene().at(options.time + time);
+                options.rasterize_scene(&viewport, scene)
+            }
+        };
+        options.depth_cue(&mut framebuffer, watcher.scene().depth_range());
+        output.extend(b"\x1b[H");
+        ansi::encode(
+            &mut output,
+            &framebuffer,
+            options.render_mode,
+            options.color_mode,
+        )?;
+        if let Some(error) = watcher.error() {
+            write_overlay(&mut output, &error.to_string(), width)?;
+        }
+        show(time, &output)?;
+        output.clear();
     }
+    Ok(())
 }
@@ -0,0 +1,489 @@
+use std::f64::consts::TAU;
+
+use crate::{random::Random, Color, Hit, Hittable, Material, Ray, Vector};
+
+/// How far off surfaces rays leaving them start, so that they do not hit the surface
+/// they leave.
+const EPSILON: f64 = 1e-4;
+
+/// A source of light in a [`Lighting`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Light {
+    /// Light shining out in every direction from a point, as bright at any distance.
+    Point {
+        /// Where the light is.
+        position: Vector,
+        /// The color and brightness of the light.
+        color: Color,
+    },
+    /// Light shining in one direction everywhere, like sunlight.
+    Directional {
+        /// The direction the light shines in.
+        direction: Vector,
+        /// The color and brightness of the light.
+        color: Color,
+    },
+}
+
+impl Light {
+    /// Returns the direction toward this light from `point` with a length of 1, and the
+    /// distance to it.
+    fn toward(&self, point: Vector) -> (Vector, f64) {
+        match *self {
+            Self::Point { position, .. } => {
+                let offset = position - point;
+                (offset.normalized(), offset.len())
+            }
+            Self::Directional { direction, .. } => (-direction.normalized(), f64::INFINITY),
+        }
+    }
+
+    /// Returns the color of this light.
+    fn color(&self) -> Color {
+        match *self {
+            Self::Point { color, .. } | Self::Directional { color, .. } => color,
+        }
+    }
+}
+
+/// The lights of a scene and how light bounces between its objects, for
+/// [`HittableExt::lit`](crate::HittableExt::lit).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Lighting {
+    /// The lights shining on the scene.
+    pub lights: Vec<Light>,
+    /// The light that reaches everywhere, even where the lights are in shadow, except
+    /// when path traced, where the light bouncing between objects takes its place.
+    pub ambient: Color,
+    /// The color seen in reflections and through objects where nothing is hit, for
+    /// scenes without a [background](crate::HittableExt::background) of their own,
+    /// which also lights the scene like a sky when it is path traced.
+    pub background: Color,
+    /// How many times rays bounce off mirrors and through clear objects, or off any
+    /// surface when path traced.
+    pub max_depth: u32,
+}
+
+impl Lighting {
+    /// Returns lighting by `lights`, with a little ambient light and a black
+    /// background, bouncing rays up to 5 times.
+    pub fn new(lights: Vec<Light>) -> Self {
+        Self {
+            lights,
+            ambient: Color(0.1, 0.1, 0.1),
+            background: Color::BLACK,
+            max_depth: 5,
+        }
+    }
+
+    /// Returns the color that `incidence` sees in `scene`, bounced `depth` times before.
+    fn trace(&self, scene: &impl Hittable, incidence: Ray, depth: u32) -> Color {
+        match scene.hit(incidence) {
+            Some(hit) => self.shade(scene, incidence, &hit, depth),
+            None => self.background,
+        }
+    }
+
+    /// Returns how much of a light is let through to `point` from the `direction` of
+    /// the light at `distance`, past whatever objects of `scene` are in the way.
+    fn shadow(
+        &self,
+        scene: &impl Hittable,
+        point: Vector,
+        direction: Vector,
+        distance: f64,
+    ) -> f64 {
+        let mut through = 1.0;
+        let mut origin = point;
+        let mut left = distance;
+        // Clear objects cast lighter shadows, which each of their surfaces darkens.
+        for _ in 0..=self.max_depth {
+            match scene.hit(Ray { origin, direction }) {
+                Some(hit) if hit.t < left => {
+                    through *= hit.material.transparency;
+                    if through <= 0.0 {
+                        return 0.0;
+                    }
+                    origin += direction * (hit.t + EPSILON);
+                    left -= hit.t + EPSILON;
+                }
+                _ => return through,
+            }
+        }
+        through
+    }
+
+    /// Returns the light that shines on `point` of a surface of `hit` facing `normal`
+    /// straight from these lights and on toward `direction`, scattered by its color and
+    /// with highlights.
+    fn direct(
+        &self,
+        scene: &impl Hittable,
+        hit: &Hit,
+        direction: Vector,
+        point: Vector,
+        normal: Vector,
+    ) -> Color {
+        let material = hit.material;
+        let above = point + normal * EPSILON;
+        let mut color = Color::BLACK;
+        for light in &self.lights {
+            let (toward, distance) = light.toward(point);
+            let facing = normal.dot(toward);
+            if facing <= 0.0 {
+                continue;
+            }
+            let shadow = self.shadow(scene, above, toward, distance);
+            if shadow <= 0.0 {
+                continue;
+            }
+            let light = light.color() * shadow;
+            color += light * hit.color * (material.diffuse * facing);
+            let halfway = (toward - direction).normalized();
+            let highlight = normal.dot(halfway).max(0.0).powf(material.shininess);
+            color += light * (material.specular * highlight);
+        }
+        color
+    }
+
+    /// Returns the color of `hit`, struck by `incidence` in `scene` after bouncing
+    /// `depth` times, lit by these lights and reflecting and letting through the rest
+    /// of the scene as its material says.
+    fn shade(&self, scene: &impl Hittable, incidence: Ray, hit: &Hit, depth: u32) -> Color {
+        if hit.normal == Vector::ZERO {
+            return hit.color;
+        }
+        let material = hit.material;
+        let direction = incidence.direction.normalized();
+        let point = incidence.at(hit.t);
+        // The normal on the side of the surface that was hit.
+        let entering = direction.dot(hit.normal) < 0.0;
+        let normal = if entering { hit.normal } else { -hit.normal };
+        let above = point + normal * EPSILON;
+
+        let surface = material.emission
+            + self.ambient * hit.color * material.diffuse
+            + self.direct(scene, hit, direction, point, normal);
+        let opaque = (1.0 - material.reflectivity - material.transparency).max(0.0);
+        if depth >= self.max_depth || opaque >= 1.0 {
+            return surface;
+        }
+        let reflect = || {
+            let ray = Ray {
+                origin: above,
+                direction: mirror(direction, normal),
+            };
+            self.trace(scene, ray, depth + 1)
+        };
+
+        let mut color = surface * opaque;
+        if material.reflectivity > 0.0 {
+            color += reflect() * material.reflectivity;
+        }
+        if material.transparency > 0.0 {
+            let (from, to) = match entering {
+                true => (1.0, material.refractive_index),
+                false => (material.refractive_index, 1.0),
+            };
+            let cos = -direction.dot(normal);
+            let reflected = Material::fresnel(from, to, cos);
+            let mut through = reflect() * reflected;
+            if reflected < 1.0 {
+                let ray = Ray {
+                    origin: point - normal * EPSILON,
+                    direction: refract(direction, normal, from / to),
+                };
+                through += self.trace(scene, ray, depth + 1) * hit.color * (1.0 - reflected);
+            }
+            color += through * material.transparency;
+        }
+        color
+    }
+
+    /// Returns one random estimate of the light that `incidence` sees in `scene`,
+    /// bounced `depth` times before, drawing on `random` for the way it bounces.
+    fn path(
+        &self,
+        scene: &impl Hittable,
+        incidence: Ray,
+        random: &mut Random,
+        depth: u32,
+    ) -> Color {
+        match scene.hit(incidence) {
+            Some(hit) => self.sample(scene, incidence, &hit, random, depth),
+            None => self.background,
+        }
+    }
+
+    /// Returns one random estimate of the light leaving `hit` back along `incidence` in
+    /// `scene`, after bouncing `depth` times, by following a single path of light on
+    /// from it.
+    ///
+    /// Rather than splitting at a surface, the path goes one of the ways its material
+    /// sends light, as likely as the share of light that goes that way, so that the
+    /// average of many paths is the light of all of them together.
+    fn sample(
+        &self,
+        scene: &impl Hittable,
+        incidence: Ray,
+        hit: &Hit,
+        random: &mut Random,
+        depth: u32,
+    ) -> Color {
+        if hit.normal == Vector::ZERO {
+            return hit.color;
+        }
+        let material = hit.material;
+        if depth >= self.max_depth {
+            return material.emission;
+        }
+        let direction = incidence.direction.normalized();
+        let point = incidence.at(hit.t);
+        let entering = direction.dot(hit.normal) < 0.0;
+        let normal = if entering { hit.normal } else { -hit.normal };
+        let above = point + normal * EPSILON;
+        let follow = |origin, direction, random: &mut Random| {
+            self.path(scene, Ray { origin, direction }, random, depth + 1)
+        };
+
+        let choice = random.next_f64();
+        if choice < material.reflectivity {
+            return material.emission + follow(above, mirror(direction, normal), random);
+        }
+        if choice < material.reflectivity + material.transparency {
+            let (from, to) = match entering {
+                true => (1.0, material.refractive_index),
+                false => (material.refractive_index, 1.0),
+            };
+            let reflected = Material::fresnel(from, to, -direction.dot(normal));
+            // The choice is spread evenly over the share of light let through.
+            let share = (choice - material.reflectivity) / material.transparency;
+            if share < reflected {
+                return material.emission + follow(above, mirror(direction, normal), random);
+            }
+            let below = point - normal * EPSILON;
+            let through = follow(below, refract(direction, normal, from / to), random);
+            return material.emission + through * hit.color;
+        }
+
+        let direct = self.direct(scene, hit, direction, point, normal);
+        let bounced = follow(above, scatter(normal, random), random);
+        material.emission + direct + bounced * hit.color * material.diffuse
+    }
+}
+
+/// Returns `direction` reflected off a surface facing `normal`, like off a mirror.
+fn mirror(direction: Vector, normal: Vector) -> Vector {
+    direction - normal * (2.0 * direction.dot(normal))
+}
+
+/// Returns `direction`, of length 1, bent passing through a surface facing `normal` by
+/// the `ratio` of the refractive indices before and after it, unless it is reflected
+/// entirely.
+fn refract(direction: Vector, normal: Vector, ratio: f64) -> Vector {
+    let cos = -direction.dot(normal);
+    let k = 1.0 - ratio * ratio * (1.0 - cos * cos);
+    direction * ratio + normal * (ratio * cos - k.sqrt())
+}
+
+/// Returns a random direction off a surface facing `normal`, of length 1, which is
+/// likelier the closer it is to the normal, as light scattered by a matte surface is.
+fn scatter(normal: Vector, random: &mut Random) -> Vector {
+    // A random point on a sphere touching the surface is such a direction from it.
+    let z = 2.0 * random.next_f64() - 1.0;
+    let angle = TAU * random.next_f64();
+    let radius = (1.0 - z * z).sqrt();
+    let direction = normal + Vector(radius * angle.cos(), radius * angle.sin(), z);
+    match direction.len_squared() {
+        len_squared if len_squared > EPSILON => direction.normalized(),
+        _ => normal,
+    }
+}
+
+/// Created by [`HittableExt::lit`](crate::HittableExt::lit).
+#[derive(Clone, Debug)]
+pub struct Lit<H> {
+    hittable: H,
+    lighting: Lighting,
+}
+
+impl<H> Lit<H> {
+    /// Returns `hittable` lit by `lighting`.
+    pub(crate) fn new(hittable: H, lighting: Lighting) -> Self {
+        Self { hittable, lighting }
+    }
+}
+
+impl<H: Hittable> Hittable for Lit<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.hittable.hit(incidence).map(|hit| Hit {
+            color: self.lighting.shade(&self.hittable, incidence, &hit, 0),
+            ..hit
+        })
+    }
+}
+
+/// Created by [`HittableExt::path_traced`](crate::HittableExt::path_traced).
+#[derive(Clone, Debug)]
+pub struct PathTraced<H> {
+    hittable: H,
+    lighting: Lighting,
+}
+
+impl<H> PathTraced<H> {
+    /// Returns `hittable` path traced with `lighting`.
+    pub(crate) fn new(hittable: H, lighting: Lighting) -> Self {
+        Self { hittable, lighting }
+    }
+}
+
+impl<H: Hittable> Hittable for PathTraced<H> {
+    fn hit(&self, incidence: Ray) -> Option<Hit> {
+        self.hittable.hit(incidence).map(|hit| {
+            // Drawing the path from the ray itself makes it the same for the same ray
+            // every time, and different for every other ray cast.
+            let Ray { origin, direction } = incidence;
+            let mut random = Random::from_values(
+                [origin, direction]
+                    .into_iter()
+                    .flat_map(|vector| [vector.x(), vector.y(), vector.z()])
+                    .map(f64::to_bits),
+            );
+            Hit {
+                color: self
+                    .lighting
+                    .sample(&self.hittable, incidence, &hit, &mut random, 0),
+                ..hit
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Light, Lighting};
+    use crate::{
+        cube, util::check_about, Color, Hittable, HittableExt, Material, Matrix, Ray, Translation,
+        Vector,
+    };
+
+    fn ahead() -> Ray {
+        Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        }
+    }
+
+    fn light(position: Vector) -> Lighting {
+        Lighting {
+            ambient: Color::BLACK,
+            ..Lighting::new(vec![Light::Point {
+                position,
+                color: Color::WHITE,
+            }])
+        }
+    }
+
+    #[test]
+    fn diffuse() {
+        let scene = cube()
+            .colorize(Color::RED)
+            .transform(Translation(Vector(0.0, 3.0, 0.0)));
+        // Lit head on, the side facing the camera is as bright as its color.
+        let hit = (&scene).lit(light(Vector::ZERO)).hit(ahead()).unwrap();
+        assert_eq!(hit.color, Color::RED);
+        check_about(hit.t, 2.5);
+        // Lit from the side at 60 degrees, it is half as bright.
+        let side = Vector(3f64.sqrt() * 2.5, 0.0, 0.0);
+        let hit = (&scene).lit(light(side)).hit(ahead()).unwrap();
+        check_about(hit.color.0, 0.5);
+        // Lit from behind, it is dark.
+        let hit = (&scene)
+            .lit(light(Vector(0.0, 9.0, 0.0)))
+            .hit(ahead())
+            .unwrap();
+        assert_eq!(hit.color, Color::BLACK);
+    }
+
+    #[test]
+    fn shadows() {
+        let far = cube()
+            .colorize(Color::WHITE)
+            .transform(Translation(Vector(0.0, 5.0, 0.0)));
+        // Above the view of the far cube, but in the way of the light on it.
+        let blocker = cube()
+            .colorize(Color::WHITE)
+            .transform(Matrix::scale(Vector(0.5, 0.5, 0.5)))
+            .transform(Translation(Vector(0.0, 2.25, 1.5)));
+        let lighting = light(Vector(0.0, 0.0, 3.0));
+        let unblocked = (&far).lit(lighting.clone()).hit(ahead()).unwrap();
+        check_about(unblocked.color.0, 4.5 / 4.5f64.hypot(3.0));
+        let blocked = far.and(blocker).lit(lighting.clone()).hit(ahead()).unwrap();
+        check_about(blocked.t, 4.5);
+        assert_eq!(blocked.color, Color::BLACK);
+
+        // A clear blocker lets some light through.
+        let clear = blocker.material(Material {
+            transparency: 0.5,
+            ..Material::MATTE
+        });
+        let shaded = far.and(clear).lit(lighting).hit(ahead()).unwrap();
+        check_about(shaded.color.0, unblocked.color.0 * 0.25);
+    }
+
+    #[test]
+    fn mirror() {
+        // A mirror turned 45 degrees reflects the ray to the right, onto a green cube.
+        let mirror = cube()
+            .colorize(Color::WHITE)
+            .material(Material {
+                diffuse: 0.0,
+                specular: 0.0,
+                reflectivity: 1.0,
+                ..Material::MATTE
+            })
+            .transform(Matrix::scale(Vector(2.0, 0.1, 2.0)))
+            .transform(Matrix::rotation_z(std::f64::consts::FRAC_PI_4))
+            .transform(Translation(Vector(0.0, 3.0, 0.0)));
+        let target = cube()
+            .colorize(Color::GREEN)
+            .transform(Translation(Vector(3.0, 3.0, 0.0)));
+        let lighting = Lighting {
+            ambient: Color::WHITE,
+            ..Lighting::new(Vec::new())
+        };
+        let scene = mirror.and(target).lit(lighting.clone());
+        assert_eq!(scene.hit(ahead()).unwrap().color, Color::GREEN);
+
+        // Without anything to reflect, the mirror shows the background.
+        let lighting = Lighting {
+            background: Color::BLUE,
+            ..lighting
+        };
+        assert_eq!(
+            mirror.lit(lighting).hit(ahead()).unwrap().color,
+            Color::BLUE
+        );
+    }
+
+    #[test]
+    fn glass() {
+        let behind = cube()
+            .colorize(Color::YELLOW)
+            .transform(Translation(Vector(0.0, 6.0, 0.0)));
+        let pane = cube()
+            .colorize(Color::WHITE)
+            .material(Material::glass(1.5))
+            .transform(Matrix::scale(Vector(3.0, 0.5, 3.0)))
+            .transform(Translation(Vector(0.0, 3.0, 0.0)));
+        let lighting = Lighting {
+            ambient: Color::WHITE,
+            ..Lighting::new(Vec::new())
+        };
+        // Head on, the pane lets through all but the light reflected off its two sides,
+        // and some of that is reflected back out again.
+        let color = pane.and(behind).lit(lighting).hit(ahead()).unwrap().color;
+        check_about(color.0, 0.96 * 0.96 * (1.0 + 0.04 * 0.04));
+        check_about(color.2, 0.0);
+    }
+}
@@ -1,7 +1,28 @@
-use std::process;
+use std::{env, process::ExitCode};
 
-fn main() {
-    let error = shapes::run();
-    eprintln!("{}", error);
-    process::exit(1);
+use shapes::{
+    cli::{self, Command},
+    Error,
+};
+
+fn main() -> ExitCode {
+    let result = cli::parse(env::args().skip(1))
+        .map_err(Error::from)
+        .and_then(|command| match command {
+            Command::Help => {
+                print!("{}", cli::USAGE);
+                Ok(())
+            }
+            Command::Run(options) => shapes::run(&options),
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("shapes: {}", error);
+            if let Error::Args(_) = error {
+                eprintln!("\nFor more information, try `--help`.");
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
 }
@@ -0,0 +1,124 @@
+use crate::Color;
+
+/// How the surface of an object reflects and lets through light, when a scene is
+/// [lit](crate::HittableExt::lit).
+///
+/// The parts of a material add up: light that is not reflected by the mirror or let
+/// through is scattered by the surface in the color of the [`Hit`](crate::Hit), with a
+/// highlight on top, and any light the surface gives off of its own.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Material {
+    /// How much light the surface scatters evenly in every direction, from 0 to 1.
+    pub diffuse: f64,
+    /// How bright the highlights of lights on the surface are, from 0 to 1.
+    pub specular: f64,
+    /// How tight the highlights are, from about 1 for broad ones to hundreds for pinpricks.
+    pub shininess: f64,
+    /// How much of the scene the surface reflects like a mirror, from 0 to 1.
+    pub reflectivity: f64,
+    /// How much light passes through the surface, from 0 to 1, tinted by its color.
+    pub transparency: f64,
+    /// How much light bends passing into the object, such as 1.5 for glass.
+    pub refractive_index: f64,
+    /// The light the surface gives off of its own, which makes the object an area light
+    /// when the scene is [path traced](crate::HittableExt::path_traced).
+    pub emission: Color,
+}
+
+impl Material {
+    /// A surface that scatters all light evenly, like chalk.
+    pub const MATTE: Self = Self {
+        diffuse: 1.0,
+        specular: 0.0,
+        shininess: 32.0,
+        reflectivity: 0.0,
+        transparency: 0.0,
+        refractive_index: 1.0,
+        emission: Color::BLACK,
+    };
+
+    /// A smooth surface with bright highlights, like plastic.
+    pub const GLOSSY: Self = Self {
+        specular: 0.6,
+        shininess: 64.0,
+        ..Self::MATTE
+    };
+
+    /// A mirror, which reflects nearly all light.
+    pub const MIRROR: Self = Self {
+        diffuse: 0.1,
+        specular: 1.0,
+        shininess: 256.0,
+        reflectivity: 0.9,
+        ..Self::MATTE
+    };
+
+    /// Returns a clear material like glass, bending light by `refractive_index` and
+    /// reflecting more of it the more glancing it is.
+    pub fn glass(refractive_index: f64) -> Self {
+        Self {
+            diffuse: 0.0,
+            specular: 1.0,
+            shininess: 256.0,
+            reflectivity: 0.0,
+            transparency: 1.0,
+            refractive_index,
+            emission: Color::BLACK,
+        }
+    }
+
+    /// Returns a matte material that gives off light of `color`, such as a lamp.
+    pub fn emissive(color: Color) -> Self {
+        Self {
+            emission: color,
+            ..Self::MATTE
+        }
+    }
+
+    /// Returns the fraction of light that is reflected rather than let through at a
+    /// boundary where it passes from a refractive index of `from` to `to`, at an angle
+    /// to the normal whose cosine is `cos`, by Schlick's approximation of the Fresnel
+    /// equations.
+    pub(crate) fn fresnel(from: f64, to: f64, cos: f64) -> f64 {
+        if from == to {
+            return 0.0;
+        }
+        let mut cos = cos;
+        if from > to {
+            // Light passing into a thinner medium is reflected entirely past an angle.
+            let ratio = from / to;
+            let sin_squared = ratio * ratio * (1.0 - cos * cos);
+            if sin_squared > 1.0 {
+                return 1.0;
+            }
+            cos = (1.0 - sin_squared).sqrt();
+        }
+        let r0 = ((from - to) / (from + to)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+impl Default for Material {
+    /// Returns a [matte](Self::MATTE) material.
+    fn default() -> Self {
+        Self::MATTE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Material;
+    use crate::util::check_about;
+
+    #[test]
+    fn fresnel() {
+        // Head-on, glass reflects a few percent of light.
+        check_about(Material::fresnel(1.0, 1.5, 1.0), 0.04);
+        check_about(Material::fresnel(1.5, 1.0, 1.0), 0.04);
+        // At a glancing angle it reflects nearly everything.
+        assert!(Material::fresnel(1.0, 1.5, 0.01) > 0.9);
+        // Leaving glass past the critical angle reflects everything.
+        check_about(Material::fresnel(1.5, 1.0, 0.5), 1.0);
+        check_about(Material::fresnel(1.0, 1.0, 0.3), 0.0);
+    }
+}
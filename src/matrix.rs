@@ -4,18 +4,18 @@ use crate::{Transformation, Vector};
 
 /// A 3x3 matrix.
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct Matrix(
+pub struct Matrix(
     /// The first row of this matrix.
-    Vector,
+    pub Vector,
     /// The second row of this matrix.
-    Vector,
+    pub Vector,
     /// The third row of this matrix.
-    Vector,
+    pub Vector,
 );
 
 impl Matrix {
     /// Returns the identity matrix.
-    pub(crate) fn identity() -> Matrix {
+    pub fn identity() -> Matrix {
         Self(
             Vector(1.0, 0.0, 0.0),
             Vector(0.0, 1.0, 0.0),
@@ -24,7 +24,7 @@ impl Matrix {
     }
 
     /// Returns a matrix that causes a rotation of `angle` in the x-axis.
-    pub(crate) fn rotation_x(angle: f64) -> Matrix {
+    pub fn rotation_x(angle: f64) -> Matrix {
         let (sin, cos) = angle.sin_cos();
         Self(
             Vector(1.0, 0.0, 0.0),
@@ -34,7 +34,7 @@ impl Matrix {
     }
 
     /// Returns a matrix that causes a rotation of `angle` in the y-axis.
-    pub(crate) fn rotation_y(angle: f64) -> Matrix {
+    pub fn rotation_y(angle: f64) -> Matrix {
         let (sin, cos) = angle.sin_cos();
         Self(
             Vector(cos, 0.0, sin),
@@ -44,7 +44,7 @@ impl Matrix {
     }
 
     /// Returns a matrix that causes a rotation of `angle` in the z-axis.
-    pub(crate) fn rotation_z(angle: f64) -> Matrix {
+    pub fn rotation_z(angle: f64) -> Matrix {
         let (sin, cos) = angle.sin_cos();
         Self(
             Vector(cos, -sin, 0.0),
@@ -54,7 +54,7 @@ impl Matrix {
     }
 
     /// Returns a matrix that causes a scaling of `factor` in the corresponding axes.
-    pub(crate) fn scale(Vector(x, y, z): Vector) -> Matrix {
+    pub fn scale(Vector(x, y, z): Vector) -> Matrix {
         Self(
             Vector(x, 0.0, 0.0),
             Vector(0.0, y, 0.0),
@@ -63,7 +63,7 @@ impl Matrix {
     }
 
     /// Returns the determinant of this matrix.
-    pub(crate) fn determinant(self) -> f64 {
+    pub fn determinant(self) -> f64 {
         self.0 .0 * (self.1 .1 * self.2 .2 - self.2 .1 * self.1 .2)
             - self.0 .1 * (self.1 .0 * self.2 .2 - self.2 .0 * self.1 .2)
             + self.0 .2 * (self.1 .0 * self.2 .1 - self.2 .0 * self.1 .1)
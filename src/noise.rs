@@ -0,0 +1,256 @@
+use crate::{random::Random, Vector};
+
+/// The directions of gradients, from the centers of the edges of a cube to its center,
+/// as in Ken Perlin's improved noise.
+const GRADIENTS: [Vector; 12] = [
+    Vector(1.0, 1.0, 0.0),
+    Vector(-1.0, 1.0, 0.0),
+    Vector(1.0, -1.0, 0.0),
+    Vector(-1.0, -1.0, 0.0),
+    Vector(1.0, 0.0, 1.0),
+    Vector(-1.0, 0.0, 1.0),
+    Vector(1.0, 0.0, -1.0),
+    Vector(-1.0, 0.0, -1.0),
+    Vector(0.0, 1.0, 1.0),
+    Vector(0.0, -1.0, 1.0),
+    Vector(0.0, 1.0, -1.0),
+    Vector(0.0, -1.0, -1.0),
+];
+
+/// Seeded gradient noise: smooth pseudorandom values that vary over space, for textures,
+/// clouds and camera shake.
+///
+/// The noise is the same for the same seed every time, on any machine, so it can be used
+/// in golden tests. Its values are roughly from -1 to 1, and 0 at every point with whole
+/// coordinates for Perlin noise, with features about 1 unit apart.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GradientNoise {
+    /// The seed the noise is laid out by, each seed giving different noise.
+    pub seed: u64,
+}
+
+impl GradientNoise {
+    /// Returns the noise laid out by `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns the gradient at the lattice point `cell`.
+    fn gradient<const N: usize>(self, cell: [f64; N]) -> Vector {
+        let hash = cell.iter().fold(self.seed, |hash, &n| {
+            Random::new(hash ^ n as i64 as u64).next_u64()
+        });
+        GRADIENTS[(hash % 12) as usize]
+    }
+
+    /// Returns 2D Perlin noise at `(x, y)`.
+    pub fn perlin2(self, x: f64, y: f64) -> f64 {
+        let (i, j) = (x.floor(), y.floor());
+        let (fx, fy) = (x - i, y - j);
+        let corner = |dx: f64, dy: f64| {
+            let gradient = self.gradient([i + dx, j + dy]);
+            gradient.x() * (fx - dx) + gradient.y() * (fy - dy)
+        };
+        let (sx, sy) = (fade(fx), fade(fy));
+        let bottom = lerp(corner(0.0, 0.0), corner(1.0, 0.0), sx);
+        let top = lerp(corner(0.0, 1.0), corner(1.0, 1.0), sx);
+        lerp(bottom, top, sy)
+    }
+
+    /// Returns 3D Perlin noise at `point`.
+    pub fn perlin3(self, point: Vector) -> f64 {
+        let cell = Vector(point.x().floor(), point.y().floor(), point.z().floor());
+        let fraction = point - cell;
+        let corner = |dx: f64, dy: f64, dz: f64| {
+            let offset = Vector(dx, dy, dz);
+            let gradient = self.gradient([cell.x() + dx, cell.y() + dy, cell.z() + dz]);
+            gradient.dot(fraction - offset)
+        };
+        let (sx, sy, sz) = (fade(fraction.x()), fade(fraction.y()), fade(fraction.z()));
+        let along_x = |dy, dz| lerp(corner(0.0, dy, dz), corner(1.0, dy, dz), sx);
+        let along_y = |dz| lerp(along_x(0.0, dz), along_x(1.0, dz), sy);
+        lerp(along_y(0.0), along_y(1.0), sz)
+    }
+
+    /// Returns 2D simplex noise at `(x, y)`, which is quicker than Perlin noise and has
+    /// fewer artifacts along the axes.
+    pub fn simplex2(self, x: f64, y: f64) -> f64 {
+        // Skews the plane so that its triangles become half squares, and back.
+        let skew = 0.5 * (3f64.sqrt() - 1.0);
+        let unskew = (3.0 - 3f64.sqrt()) / 6.0;
+
+        let s = (x + y) * skew;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * unskew;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+        // Which of the two triangles of the square the point is in.
+        let (di, dj) = if x0 > y0 { (1.0, 0.0) } else { (0.0, 1.0) };
+
+        let corner = |ci: f64, cj: f64| {
+            let (x, y) = (x0 - ci + (ci + cj) * unskew, y0 - cj + (ci + cj) * unskew);
+            let t = 0.5 - x * x - y * y;
+            if t < 0.0 {
+                return 0.0;
+            }
+            let gradient = self.gradient([i + ci, j + cj]);
+            t.powi(4) * (gradient.x() * x + gradient.y() * y)
+        };
+        70.0 * (corner(0.0, 0.0) + corner(di, dj) + corner(1.0, 1.0))
+    }
+
+    /// Returns 3D simplex noise at `point`, which is quicker than Perlin noise and has
+    /// fewer artifacts along the axes.
+    pub fn simplex3(self, point: Vector) -> f64 {
+        // Skews space so that its tetrahedra become sixths of cubes, and back.
+        let (skew, unskew) = (1.0 / 3.0, 1.0 / 6.0);
+
+        let s = (point.x() + point.y() + point.z()) * skew;
+        let cell = Vector(
+            (point.x() + s).floor(),
+            (point.y() + s).floor(),
+            (point.z() + s).floor(),
+        );
+        let t = (cell.x() + cell.y() + cell.z()) * unskew;
+        let offset = point - (cell - Vector(t, t, t));
+        // The corners of the tetrahedron the point is in, from the first corner of its
+        // cube to the opposite one, along the axes in order of the offset along them.
+        let (x, y, z) = (offset.x(), offset.y(), offset.z());
+        let (second, third) = if x >= y {
+            if y >= z {
+                (Vector(1.0, 0.0, 0.0), Vector(1.0, 1.0, 0.0))
+            } else if x >= z {
+                (Vector(1.0, 0.0, 0.0), Vector(1.0, 0.0, 1.0))
+            } else {
+                (Vector(0.0, 0.0, 1.0), Vector(1.0, 0.0, 1.0))
+            }
+        } else if y < z {
+            (Vector(0.0, 0.0, 1.0), Vector(0.0, 1.0, 1.0))
+        } else if x < z {
+            (Vector(0.0, 1.0, 0.0), Vector(0.0, 1.0, 1.0))
+        } else {
+            (Vector(0.0, 1.0, 0.0), Vector(1.0, 1.0, 0.0))
+        };
+
+        let corner = |corner: Vector| {
+            let shift = (corner.x() + corner.y() + corner.z()) * unskew;
+            let position = offset - corner + Vector(shift, shift, shift);
+            let t = 0.6 - position.len_squared();
+            if t < 0.0 {
+                return 0.0;
+            }
+            let lattice = cell + corner;
+            let gradient = self.gradient([lattice.x(), lattice.y(), lattice.z()]);
+            t.powi(4) * gradient.dot(position)
+        };
+        32.0 * (corner(Vector::ZERO)
+            + corner(second)
+            + corner(third)
+            + corner(Vector(1.0, 1.0, 1.0)))
+    }
+
+    /// Returns fractal Brownian motion at `point`: `octaves` layers of Perlin noise, each
+    /// with features half the size and half as strong as the layer before, for detail at
+    /// every scale like that of clouds or rock.
+    pub fn fbm(self, point: Vector, octaves: u32) -> f64 {
+        self.octaves(point, octaves, |noise| noise)
+    }
+
+    /// Returns turbulence at `point`: like [`fbm`](Self::fbm), but of the size of each
+    /// layer, for billowing patterns like those of fire and marble. Its values are from
+    /// 0 to about 1.
+    pub fn turbulence(self, point: Vector, octaves: u32) -> f64 {
+        self.octaves(point, octaves, f64::abs)
+    }
+
+    /// Returns the sum of `octaves` layers of Perlin noise at `point`, each layer passed
+    /// through `layer`, scaled so that the sum stays within the range of one layer.
+    fn octaves(self, point: Vector, octaves: u32, layer: impl Fn(f64) -> f64) -> f64 {
+        let (mut sum, mut total, mut amplitude, mut frequency) = (0.0, 0.0, 1.0, 1.0);
+        for octave in 0..octaves.max(1) {
+            // Each layer is of different noise, so their lattices do not line up.
+            let noise = Self::new(self.seed.wrapping_add(octave as u64));
+            sum += amplitude * layer(noise.perlin3(point * frequency));
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / total
+    }
+}
+
+/// Eases `t` from 0 to 1 with no change in slope or curvature at either end.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Returns the value `t` of the way from `a` to `b`.
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GradientNoise;
+    use crate::{util::check_about, Vector};
+
+    /// Returns points along a wiggly line through a few cells of noise.
+    fn points() -> impl Iterator<Item = Vector> {
+        (0..2000).map(|i| {
+            let t = i as f64 * 0.01;
+            Vector(
+                t * 0.7 - 3.0,
+                (t * 1.3).sin() * 2.0,
+                t * 0.3 + (t * 0.5).cos(),
+            )
+        })
+    }
+
+    /// Checks that `noise` stays within `range`, changes smoothly but does change, and
+    /// returns the values it took.
+    fn check(noise: impl Fn(Vector) -> f64, range: (f64, f64)) -> Vec<f64> {
+        let values: Vec<_> = points().map(noise).collect();
+        for pair in values.windows(2) {
+            assert!((range.0..=range.1).contains(&pair[0]), "{}", pair[0]);
+            assert!((pair[1] - pair[0]).abs() < 0.2);
+        }
+        let (min, max) = values.iter().fold((f64::MAX, f64::MIN), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+        assert!(max - min > (range.1 - range.0) / 4.0);
+        values
+    }
+
+    #[test]
+    fn perlin() {
+        let noise = GradientNoise::new(1);
+        check(|p| noise.perlin3(p), (-1.0, 1.0));
+        check(|p| noise.perlin2(p.x(), p.y()), (-1.0, 1.0));
+        check_about(noise.perlin3(Vector(2.0, -5.0, 1.0)), 0.0);
+        check_about(noise.perlin2(-1.0, 3.0), 0.0);
+        // Noise is the same each time, and different for different seeds.
+        let point = Vector(0.3, 0.6, 0.2);
+        assert_eq!(noise.perlin3(point), GradientNoise::new(1).perlin3(point));
+        assert_ne!(noise.perlin3(point), GradientNoise::new(2).perlin3(point));
+    }
+
+    #[test]
+    fn simplex() {
+        let noise = GradientNoise::new(5);
+        check(|p| noise.simplex3(p), (-1.0, 1.0));
+        check(|p| noise.simplex2(p.x(), p.z()), (-1.0, 1.0));
+        let point = Vector(1.3, -0.6, 0.2);
+        assert_ne!(noise.simplex3(point), GradientNoise::new(6).simplex3(point));
+    }
+
+    #[test]
+    fn fractal() {
+        let noise = GradientNoise::new(9);
+        check(|p| noise.fbm(p, 4), (-1.0, 1.0));
+        let turbulence = check(|p| noise.turbulence(p, 4), (0.0, 1.0));
+        // One octave is just Perlin noise.
+        let point = Vector(0.3, 0.6, 0.2);
+        assert_eq!(noise.fbm(point, 1), noise.perlin3(point));
+        assert_eq!(noise.turbulence(point, 0), noise.perlin3(point).abs());
+        assert!(turbulence.iter().all(|&value| value >= 0.0));
+    }
+}
@@ -0,0 +1,98 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::{Hittable, HittableExt, Matrix, Translation, Vector};
+
+/// A view of a scene from a point orbiting around a `target`, made by moving the scene
+/// rather than the [`Camera`](crate::Camera), which always stays at the origin.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Orbit {
+    /// The point of the scene that is orbited, which is kept in the center of the view.
+    pub target: Vector,
+    /// The angle of the orbit to the right around the z-axis, in radians.
+    pub yaw: f64,
+    /// The angle of the orbit above the target, in radians.
+    pub pitch: f64,
+    /// The distance from the camera to the target.
+    pub distance: f64,
+}
+
+impl Orbit {
+    /// The closest the camera can get to its target.
+    pub const MIN_DISTANCE: f64 = 1.5;
+
+    /// The furthest the camera can get from its target.
+    pub const MAX_DISTANCE: f64 = 30.0;
+
+    /// Returns an orbit around `target` that views it from where the camera is.
+    pub fn new(target: Vector) -> Self {
+        Self {
+            target,
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: target.len(),
+        }
+    }
+
+    /// Moves around the target by `yaw` and `pitch` radians, keeping the pitch short of
+    /// looking straight down or up.
+    pub fn rotate(&mut self, yaw: f64, pitch: f64) {
+        let limit = FRAC_PI_2 - 0.05;
+        self.yaw = (self.yaw + yaw).rem_euclid(TAU);
+        self.pitch = (self.pitch + pitch).clamp(-limit, limit);
+    }
+
+    /// Multiplies the distance to the target by `factor`, within
+    /// [`MIN_DISTANCE`](Self::MIN_DISTANCE) and [`MAX_DISTANCE`](Self::MAX_DISTANCE).
+    pub fn zoom(&mut self, factor: f64) {
+        self.distance = (self.distance * factor).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE);
+    }
+
+    /// Returns `scene` as it is seen from this orbit.
+    pub fn view(&self, scene: impl Hittable) -> impl Hittable {
+        // The scene turns around the target the opposite way to the orbit of the camera.
+        scene
+            .transform(Translation(-self.target))
+            .transform(Matrix::rotation_z(-self.yaw))
+            .transform(Matrix::rotation_x(self.pitch))
+            .transform(Translation(Vector(0.0, self.distance, 0.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::Orbit;
+    use crate::{cube, util::check_about, Color, Hittable, HittableExt, Ray, Translation, Vector};
+
+    #[test]
+    fn view() {
+        let target = Vector(0.0, 3.0, 0.0);
+        // Only the top of the cube is yellow.
+        let scene = cube()
+            .and(
+                cube()
+                    .colorize(Color::YELLOW)
+                    .transform(Translation(Vector(0.0, 0.0, 0.001))),
+            )
+            .transform(Translation(target));
+        let mut orbit = Orbit::new(target);
+        check_about(orbit.distance, 3.0);
+
+        // Looking straight down at the target from above shows the top.
+        orbit.rotate(0.0, FRAC_PI_2);
+        assert!(orbit.pitch < FRAC_PI_2);
+        orbit.pitch = FRAC_PI_2;
+        orbit.zoom(2.0);
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.0, 1.0, 0.0),
+        };
+        let hit = orbit.view(scene).hit(incidence).unwrap();
+        assert_eq!(hit.color, Color::YELLOW);
+        check_about(hit.t, 5.499);
+
+        orbit.zoom(100.0);
+        check_about(orbit.distance, Orbit::MAX_DISTANCE);
+    }
+}
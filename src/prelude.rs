@@ -0,0 +1,9 @@
+//! The types and traits needed to build and render scenes, for glob imports.
+
+pub use crate::{
+    cube, encode, Animate, Background, Camera, Checker, Color, ColorMode, Easing, EasingCurve,
+    Environment, Falloff, Fog, Framebuffer, Gradient, GradientNoise, Hit, Hittable, HittableExt,
+    ImageTexture, Light, Lighting, Marble, Material, Matrix, Noise, ObjectId, Orbit, PathTracer,
+    Ray, RenderMode, Repeat, Sampling, Scene, Sky, Spline, Starfield, Stripes, Texture, Torus,
+    Track, Transformation, TransformationExt, Translation, Vector, Viewport, Widget, Wood,
+};
@@ -0,0 +1,55 @@
+/// A small and fast pseudorandom number generator, SplitMix64, for reproducible
+/// sampling rather than anything that needs to be unpredictable.
+#[derive(Clone, Debug)]
+pub(crate) struct Random(u64);
+
+impl Random {
+    /// Returns a generator whose numbers are determined by `seed`.
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns a generator whose numbers are determined by all of `values`, in order.
+    pub(crate) fn from_values(values: impl IntoIterator<Item = u64>) -> Self {
+        let seed = values
+            .into_iter()
+            .fold(0, |hash, value| Self::new(hash ^ value).next_u64());
+        Self(seed)
+    }
+
+    /// Returns the next 64 random bits.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number uniformly distributed between 0 inclusive and 1 exclusive.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill the mantissa of an f64 exactly.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Random;
+
+    #[test]
+    fn reproducible() {
+        let mut a = Random::new(7);
+        let mut b = Random::new(7);
+        for _ in 0..100 {
+            let n = a.next_f64();
+            assert_eq!(n, b.next_f64());
+            assert!((0.0..1.0).contains(&n));
+        }
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+        assert_ne!(
+            Random::from_values([1, 2]).next_u64(),
+            Random::from_values([2, 1]).next_u64()
+        );
+    }
+}
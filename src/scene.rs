@@ -0,0 +1,178 @@
+use std::{error::Error, f64::consts::TAU, fmt, str::FromStr, sync::Arc};
+
+use crate::{
+    cube, Color, Description, Easing, EasingCurve, Hittable, HittableExt, Lighting, Matrix, Repeat,
+    Spline, Torus, Track, Translation, Vector,
+};
+
+/// Returns a track turning a full circle in radians every `period` seconds, eased by
+/// `easing`.
+fn turning(period: f64, easing: Easing) -> Track<f64> {
+    Track::new(0.0)
+        .key(period, TAU, easing)
+        .repeat(Repeat::Loop)
+}
+
+/// The names of the objects of the scenes, which they are [tagged](HittableExt::named)
+/// with.
+pub(crate) const NAMES: &[&str] = &["cube", "torus"];
+
+/// Returns the cube of the demonstration, easing through a full turn every 2.5 seconds.
+pub(crate) fn spinning_cube(time: f64) -> impl Hittable {
+    let spin = turning(2.5, Easing::InOut(EasingCurve::Sin));
+    cube()
+        .named("cube")
+        .transform(Matrix::scale(Vector(1.3, 1.3, 1.3)))
+        .transform(Matrix::rotation_z(spin.sample(time)))
+        .transform(Matrix::rotation_x(0.7))
+}
+
+/// Returns the torus of the demonstration, tumbling about all three axes.
+pub(crate) fn tumbling_torus(time: f64) -> impl Hittable {
+    let tumble = |period| turning(period, Easing::Linear).sample(time);
+    Torus {
+        radius_major: 1.5,
+        radius_minor: 0.3,
+    }
+    .named("torus")
+    .transform(Matrix::rotation_x(tumble(6.0)))
+    .transform(Matrix::rotation_y(tumble(29.0)))
+    .transform(Matrix::rotation_z(-tumble(14.0)))
+}
+
+/// The point in front of the camera that scenes are centered on.
+pub(crate) const TARGET: Vector = Vector(0.0, 3.0, 0.0);
+
+/// Moves `hittable` from the origin to [`TARGET`], in front of the camera.
+pub(crate) fn in_view(hittable: impl Hittable) -> impl Hittable {
+    hittable.transform(Translation(TARGET))
+}
+
+/// Returns the lighting of path traced scenes that have none of their own, a white sky
+/// under which objects are as bright as their colors except where they shade each other.
+pub(crate) fn daylight() -> Lighting {
+    Lighting {
+        background: Color::WHITE,
+        ..Lighting::new(Vec::new())
+    }
+}
+
+/// Returns the demonstration scene, a spinning cube threaded by a tumbling torus, as
+/// it appears `time` seconds into its animation.
+pub(crate) fn demo(time: f64) -> impl Hittable {
+    in_view(spinning_cube(time).and(tumbling_torus(time)))
+}
+
+/// The points that the flight around the demonstration passes through, the corners of a
+/// star that heads past it on every leg.
+const FLIGHT: [Vector; 5] = [
+    Vector(0.0, -10.0, 1.0),
+    Vector(5.88, 8.09, 2.0),
+    Vector(-9.51, -3.09, 0.0),
+    Vector(9.51, -3.09, 1.5),
+    Vector(-5.88, 8.09, -0.5),
+];
+
+/// Returns the demonstration as it is seen `time` seconds into a flight around it,
+/// looking ahead.
+pub(crate) fn flight(time: f64) -> impl Hittable {
+    let path = Spline::catmull_rom(&FLIGHT, true).expect("the flight has enough points");
+    let scene = spinning_cube(time).and(tumbling_torus(time));
+    path.view(scene, 2.0 * time)
+}
+
+/// A scene that can be chosen by name, or described by text.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub enum Scene {
+    /// A spinning cube threaded by a tumbling torus.
+    #[default]
+    Demo,
+    /// The cube of the demonstration on its own.
+    Cube,
+    /// The torus of the demonstration on its own.
+    Torus,
+    /// The demonstration seen from a camera flying around it.
+    Flight,
+    /// A scene described by text, such as a scene file.
+    Described(Arc<Description>),
+}
+
+impl Scene {
+    /// Returns this scene as it appears `time` seconds into its animation.
+    pub fn at(&self, time: f64) -> Box<dyn Hittable + Sync> {
+        match self {
+            Self::Demo => Box::new(demo(time)),
+            Self::Cube => Box::new(in_view(spinning_cube(time))),
+            Self::Torus => Box::new(in_view(tumbling_torus(time))),
+            Self::Flight => Box::new(flight(time)),
+            Self::Described(description) => Box::new(description.at(time)),
+        }
+    }
+
+    /// Returns this scene as it appears `time` seconds into its animation,
+    /// [path traced](HittableExt::path_traced) for a [`PathTracer`](crate::PathTracer).
+    pub fn path_traced(&self, time: f64) -> Box<dyn Hittable + Sync> {
+        match self {
+            Self::Demo => Box::new(demo(time).path_traced(daylight())),
+            Self::Cube => Box::new(in_view(spinning_cube(time)).path_traced(daylight())),
+            Self::Torus => Box::new(in_view(tumbling_torus(time)).path_traced(daylight())),
+            Self::Flight => Box::new(flight(time).path_traced(daylight())),
+            Self::Described(description) => Box::new(description.path_traced(time)),
+        }
+    }
+
+    /// Returns the point in front of the camera that this scene is centered on.
+    pub fn center(&self) -> Vector {
+        match self {
+            Self::Demo | Self::Cube | Self::Torus | Self::Flight => TARGET,
+            Self::Described(description) => description.center(),
+        }
+    }
+
+    /// Returns the distances from the camera that this scene reaches, from the nearest
+    /// to the farthest it comes as it animates, or [`None`] if the camera flies through
+    /// it.
+    ///
+    /// Described scenes can be laid out any way, so they are taken to reach from half to
+    /// one and a half times the distance to their center.
+    pub fn depth_range(&self) -> Option<(f64, f64)> {
+        // How far the objects reach from the center, however they turn.
+        let reach = match self {
+            Self::Cube => 1.3 * 3f64.sqrt() / 2.0,
+            Self::Demo | Self::Torus => 1.5 + 0.3,
+            Self::Flight => return None,
+            Self::Described(description) => description.center().len() / 2.0,
+        };
+        let distance = self.center().len();
+        Some((distance - reach, distance + reach))
+    }
+}
+
+impl FromStr for Scene {
+    type Err = SceneError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "demo" => Ok(Self::Demo),
+            "cube" => Ok(Self::Cube),
+            "torus" => Ok(Self::Torus),
+            "flight" => Ok(Self::Flight),
+            _ => Err(SceneError(format!(
+                "unknown scene `{}`, expected demo, cube, torus or flight",
+                s
+            ))),
+        }
+    }
+}
+
+/// A scene that could not be loaded.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SceneError(pub(crate) String);
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for SceneError {}
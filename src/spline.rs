@@ -0,0 +1,294 @@
+use crate::{Hittable, HittableExt, Matrix, Transformation, Translation, Vector};
+
+/// The number of steps along each segment of a [`Spline`] that its length is measured in.
+const STEPS: usize = 32;
+
+/// A spline, a smooth path through 3D space, made of cubic Bézier segments, which objects and
+/// views can move along at a constant speed.
+///
+/// Positions along a path are given as distances from its start, so that moving by the
+/// same distance covers the same length of path anywhere along it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Spline {
+    /// The start, two control points and end of each segment.
+    segments: Vec<[Vector; 4]>,
+    /// Whether the end joins back up with the start.
+    closed: bool,
+    /// The length of the path up to each step along it.
+    lengths: Vec<f64>,
+}
+
+impl Spline {
+    /// Returns a path of cubic Bézier segments, each from the end of the one before
+    /// through two control points to an end point, or `None` if there are not
+    /// `3 * n + 1` `points` for one or more segments.
+    pub fn bezier(points: &[Vector]) -> Option<Self> {
+        if points.len() < 4 || points.len() % 3 != 1 {
+            return None;
+        }
+        let segments = points
+            .windows(4)
+            .step_by(3)
+            .map(|window| [window[0], window[1], window[2], window[3]])
+            .collect();
+        Some(Self::new(segments, false))
+    }
+
+    /// Returns a Catmull-Rom spline through `points`, joining the last point back up
+    /// with the first if `closed`, or `None` if there are fewer than two points.
+    pub fn catmull_rom(points: &[Vector], closed: bool) -> Option<Self> {
+        let n = points.len();
+        if n < 2 {
+            return None;
+        }
+        // Points past the ends repeat the end points, unless the path wraps around.
+        let point = |i: isize| match closed {
+            true => points[i.rem_euclid(n as isize) as usize],
+            false => points[i.clamp(0, n as isize - 1) as usize],
+        };
+        let count = if closed { n } else { n - 1 };
+        let segments = (0..count as isize)
+            .map(|i| {
+                let (before, start, end, after) =
+                    (point(i - 1), point(i), point(i + 1), point(i + 2));
+                [
+                    start,
+                    start + (end - before) / 6.0,
+                    end - (after - start) / 6.0,
+                    end,
+                ]
+            })
+            .collect();
+        Some(Self::new(segments, closed))
+    }
+
+    /// Returns a path of `segments`, measuring its length.
+    fn new(segments: Vec<[Vector; 4]>, closed: bool) -> Self {
+        let mut path = Self {
+            segments,
+            closed,
+            lengths: Vec::new(),
+        };
+        let steps = path.segments.len() * STEPS;
+        let mut length = 0.0;
+        let mut last = path.point_at(0.0);
+        path.lengths.push(0.0);
+        for step in 1..=steps {
+            let point = path.point_at(step as f64 / STEPS as f64);
+            length += (point - last).len();
+            path.lengths.push(length);
+            last = point;
+        }
+        path
+    }
+
+    /// Returns the length of this path.
+    pub fn length(&self) -> f64 {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    /// Returns whether the end of this path joins back up with its start.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the segment and the parameter within it, from 0 to 1, of the point at
+    /// `parameter`, which counts segments from the start.
+    fn segment(&self, parameter: f64) -> ([Vector; 4], f64) {
+        let last = self.segments.len() - 1;
+        let index = (parameter.max(0.0) as usize).min(last);
+        (self.segments[index], parameter - index as f64)
+    }
+
+    /// Returns the point at `parameter`, which counts segments from the start.
+    fn point_at(&self, parameter: f64) -> Vector {
+        let ([p0, p1, p2, p3], t) = self.segment(parameter);
+        let s = 1.0 - t;
+        p0 * (s * s * s) + p1 * (3.0 * s * s * t) + p2 * (3.0 * s * t * t) + p3 * (t * t * t)
+    }
+
+    /// Returns the parameter, counting segments from the start, of the point `distance`
+    /// along this path, wrapping around if it is closed.
+    fn parameter(&self, distance: f64) -> f64 {
+        let length = self.length();
+        let distance = match self.closed {
+            true if length > 0.0 => distance.rem_euclid(length),
+            _ => distance.clamp(0.0, length),
+        };
+        // The step that the distance falls in, and how far through it the distance is.
+        let step = self
+            .lengths
+            .partition_point(|&length| length <= distance)
+            .clamp(1, self.lengths.len() - 1);
+        let (from, to) = (self.lengths[step - 1], self.lengths[step]);
+        let fraction = match to - from {
+            span if span > 0.0 => (distance - from) / span,
+            _ => 0.0,
+        };
+        (step as f64 - 1.0 + fraction) / STEPS as f64
+    }
+
+    /// Returns the point `distance` along this path.
+    pub fn point(&self, distance: f64) -> Vector {
+        self.point_at(self.parameter(distance))
+    }
+
+    /// Returns the direction of this path `distance` along it, with a length of 1.
+    pub fn tangent(&self, distance: f64) -> Vector {
+        let ([p0, p1, p2, p3], t) = self.segment(self.parameter(distance));
+        let s = 1.0 - t;
+        let derivative =
+            (p1 - p0) * (3.0 * s * s) + (p2 - p1) * (6.0 * s * t) + (p3 - p2) * (3.0 * t * t);
+        match derivative.len() {
+            len if len > 1e-12 => derivative / len,
+            // A segment with no length has no direction.
+            _ => Vector(0.0, 1.0, 0.0),
+        }
+    }
+
+    /// Returns the translation to the point `distance` along this path.
+    pub fn translation(&self, distance: f64) -> Translation {
+        Translation(self.point(distance))
+    }
+
+    /// Returns the placement at the point `distance` along this path, turned so that
+    /// the y-axis points along it and the z-axis stays as upright as it can.
+    pub fn placement(&self, distance: f64) -> Placement {
+        let forward = self.tangent(distance);
+        let right = match forward.cross(Vector(0.0, 0.0, 1.0)) {
+            // Heading straight up or down, any way is right.
+            right if right.len() < 1e-9 => Vector(1.0, 0.0, 0.0),
+            right => right.normalized(),
+        };
+        let up = right.cross(forward);
+        Placement {
+            rotation: Matrix(
+                Vector(right.x(), forward.x(), up.x()),
+                Vector(right.y(), forward.y(), up.y()),
+                Vector(right.z(), forward.z(), up.z()),
+            ),
+            position: self.point(distance),
+        }
+    }
+
+    /// Returns `scene` as it is seen from `distance` along this path, looking along it.
+    pub fn view(&self, scene: impl Hittable, distance: f64) -> impl Hittable {
+        scene.transform(self.placement(distance).inverse())
+    }
+}
+
+/// A rotation followed by a translation, such as of an object placed on a [`Spline`].
+#[derive(Copy, Clone, Debug)]
+pub struct Placement {
+    /// The rotation, applied first.
+    pub rotation: Matrix,
+    /// Where the origin is moved to after the rotation.
+    pub position: Vector,
+}
+
+impl Transformation for Placement {
+    fn transform(&self, vector: Vector) -> Vector {
+        self.rotation.transform(vector) + self.position
+    }
+
+    fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        Self {
+            rotation,
+            position: -rotation.transform(self.position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::Spline;
+    use crate::{
+        cube, util::check_about, Color, Hittable, HittableExt, Ray, Transformation, Vector,
+    };
+
+    fn check_vector(lhs: Vector, rhs: Vector) {
+        check_about((lhs - rhs).len(), 0.0);
+    }
+
+    #[test]
+    fn bezier() {
+        // A straight line with its control points bunched up at the start.
+        let path = Spline::bezier(&[
+            Vector::ZERO,
+            Vector(0.0, 0.5, 0.0),
+            Vector(0.0, 1.0, 0.0),
+            Vector(0.0, 4.0, 0.0),
+        ])
+        .unwrap();
+        check_about(path.length(), 4.0);
+        // The points are evenly spaced by distance rather than by parameter.
+        check_vector(path.point(1.0), Vector(0.0, 1.0, 0.0));
+        check_vector(path.point(3.0), Vector(0.0, 3.0, 0.0));
+        check_vector(path.point(5.0), Vector(0.0, 4.0, 0.0));
+        check_vector(path.tangent(2.0), Vector(0.0, 1.0, 0.0));
+        assert!(Spline::bezier(&[Vector::ZERO; 5]).is_none());
+        assert!(Spline::bezier(&[Vector::ZERO; 1]).is_none());
+    }
+
+    #[test]
+    fn catmull_rom() {
+        let square = [
+            Vector(1.0, 0.0, 0.0),
+            Vector(0.0, 1.0, 0.0),
+            Vector(-1.0, 0.0, 0.0),
+            Vector(0.0, -1.0, 0.0),
+        ];
+        let path = Spline::catmull_rom(&square, true).unwrap();
+        assert!(path.is_closed());
+        // The spline goes through every point, and rounds the square off into nearly a
+        // circle.
+        let quarter = path.length() / 4.0;
+        check_about(path.length() / (2.0 * PI), 0.9461);
+        for (i, &point) in square.iter().enumerate() {
+            check_vector(path.point(quarter * i as f64), point);
+        }
+        check_vector(path.point(-quarter), square[3]);
+        check_vector(path.tangent(0.0), Vector(0.0, 1.0, 0.0));
+
+        let open = Spline::catmull_rom(&square[..2], false).unwrap();
+        check_about(open.length(), 2f64.sqrt());
+        check_vector(open.point(10.0), square[1]);
+        assert!(Spline::catmull_rom(&square[..1], true).is_none());
+    }
+
+    #[test]
+    fn placement() {
+        let path = Spline::catmull_rom(&[Vector::ZERO, Vector(4.0, 0.0, 0.0)], false).unwrap();
+        let placement = path.placement(1.0);
+        // Forward is along the path, and up stays up.
+        check_vector(placement.transform(Vector::ZERO), Vector(1.0, 0.0, 0.0));
+        check_vector(
+            placement.transform(Vector(0.0, 1.0, 0.0)),
+            Vector(2.0, 0.0, 0.0),
+        );
+        check_vector(
+            placement.transform(Vector(0.0, 0.0, 1.0)),
+            Vector(1.0, 0.0, 1.0),
+        );
+        check_vector(
+            placement.inverse().transform(Vector(1.0, -1.0, 0.0)),
+            Vector(1.0, 0.0, 0.0),
+        );
+
+        // Looking along the path from its start sees the side of the cube at its end that
+        // faces back along it.
+        let scene = cube().transform(path.translation(4.0));
+        let hit = path
+            .view(scene, 0.0)
+            .hit(Ray {
+                origin: Vector::ZERO,
+                direction: Vector(0.0, 1.0, 0.0),
+            })
+            .unwrap();
+        assert_eq!(hit.color, Color::MAGENTA);
+        check_about(hit.t, 3.5);
+    }
+}
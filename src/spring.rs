@@ -0,0 +1,210 @@
+use std::{f64::consts::TAU, ops};
+
+use crate::Animate;
+
+/// A damped spring, which pulls a value toward a target as a mass on a spring would be.
+///
+/// Springs can animate a value as a function of time like an [`Easing`](crate::Easing),
+/// by [`sample`](Self::sample), or chase a target that moves, by a [`Follow`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Spring {
+    /// How many times per second the spring would oscillate if it were not damped, or 0
+    /// for a spring that does not pull at all.
+    pub frequency: f64,
+    /// How strongly the spring is damped: 1 to settle as quickly as possible without
+    /// overshooting, less to overshoot and oscillate, and more to settle more slowly.
+    pub damping: f64,
+}
+
+impl Spring {
+    /// Returns a spring oscillating `frequency` times per second when undamped, with a
+    /// damping ratio of `damping`.
+    pub fn new(frequency: f64, damping: f64) -> Self {
+        Self { frequency, damping }
+    }
+
+    /// Returns a critically damped spring, which settles as quickly as possible without
+    /// overshooting.
+    pub fn critical(frequency: f64) -> Self {
+        Self::new(frequency, 1.0)
+    }
+
+    /// Returns how the offset from the target and the velocity `time` seconds later
+    /// depend on the offset and velocity now, as the factors `[[a, b], [c, d]]` for which
+    /// the offset is `a * offset + b * velocity` and the velocity is
+    /// `c * offset + d * velocity`.
+    fn response(self, time: f64) -> [[f64; 2]; 2] {
+        let omega = TAU * self.frequency;
+        let zeta = self.damping;
+        if omega == 0.0 {
+            // With nothing pulling or damping it, the value drifts on at its velocity.
+            [[1.0, time], [0.0, 1.0]]
+        } else if (zeta - 1.0).abs() < 1e-6 {
+            let decay = (-omega * time).exp();
+            [
+                [decay * (1.0 + omega * time), decay * time],
+                [-decay * omega * omega * time, decay * (1.0 - omega * time)],
+            ]
+        } else if zeta < 1.0 {
+            let decay = (-zeta * omega * time).exp();
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let (sin, cos) = (omega_d * time).sin_cos();
+            [
+                [
+                    decay * (cos + zeta * omega / omega_d * sin),
+                    decay * sin / omega_d,
+                ],
+                [
+                    -decay * omega * omega / omega_d * sin,
+                    decay * (cos - zeta * omega / omega_d * sin),
+                ],
+            ]
+        } else {
+            // The sum of two decays, one slower than the other.
+            let root = (zeta * zeta - 1.0).sqrt();
+            let (r1, r2) = (omega * (-zeta + root), omega * (-zeta - root));
+            let (e1, e2) = ((r1 * time).exp(), (r2 * time).exp());
+            let span = r1 - r2;
+            [
+                [(r1 * e2 - r2 * e1) / span, (e1 - e2) / span],
+                [r1 * r2 * (e2 - e1) / span, (r1 * e1 - r2 * e2) / span],
+            ]
+        }
+    }
+
+    /// Returns how far a value has gone toward its target `time` seconds after being
+    /// let go from rest, starting at 0 and settling at 1, past which it can overshoot.
+    pub fn progress(self, time: f64) -> f64 {
+        if time <= 0.0 {
+            return 0.0;
+        }
+        1.0 - self.response(time)[0][0]
+    }
+
+    /// Returns the value `time` seconds after being let go from rest at `from`, on its
+    /// way to `to`.
+    pub fn sample<T: Animate>(self, from: T, to: T, time: f64) -> T {
+        from.lerp(to, self.progress(time))
+    }
+}
+
+/// A value pulled toward a `target` by a [`Spring`], which can be moved as the value
+/// goes, such as the position of a camera following an object.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Follow<T> {
+    /// The current value.
+    pub value: T,
+    /// How fast the value is changing, per second.
+    pub velocity: T,
+    /// The value that the spring pulls toward.
+    pub target: T,
+    /// The spring pulling the value.
+    pub spring: Spring,
+}
+
+impl<T> Follow<T>
+where
+    T: Copy + ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<f64, Output = T>,
+{
+    /// Returns a value at rest at its target, `value`, pulled by `spring`.
+    pub fn new(value: T, spring: Spring) -> Self {
+        Self {
+            value,
+            velocity: value * 0.0,
+            target: value,
+            spring,
+        }
+    }
+
+    /// Moves the value on by `time` seconds.
+    ///
+    /// The motion is worked out exactly, so it is the same however it is split into
+    /// updates.
+    pub fn update(&mut self, time: f64) {
+        let [[a, b], [c, d]] = self.spring.response(time);
+        let offset = self.value - self.target;
+        self.value = self.target + offset * a + self.velocity * b;
+        self.velocity = offset * c + self.velocity * d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Follow, Spring};
+    use crate::{util::check_about, Vector};
+
+    #[test]
+    fn progress() {
+        for spring in [
+            Spring::critical(2.0),
+            Spring::new(2.0, 0.3),
+            Spring::new(2.0, 3.0),
+        ] {
+            check_about(spring.progress(-1.0), 0.0);
+            check_about(spring.progress(0.0), 0.0);
+            check_about(spring.progress(0.001), 0.0);
+            check_about(spring.progress(20.0), 1.0);
+        }
+
+        // Only the underdamped spring overshoots.
+        let times = (1..200).map(|i| i as f64 / 100.0);
+        let peak = |spring: Spring| {
+            times
+                .clone()
+                .map(|t| spring.progress(t))
+                .fold(0.0, f64::max)
+        };
+        assert!(peak(Spring::new(2.0, 0.3)) > 1.3);
+        assert!(peak(Spring::critical(2.0)) <= 1.0);
+        assert!(peak(Spring::new(2.0, 3.0)) <= 1.0);
+        // An undamped spring swings to twice the distance half an oscillation in.
+        check_about(Spring::new(2.0, 0.0).progress(0.25), 2.0);
+        check_about(Spring::critical(1.0).sample(2.0, 4.0, 0.5), 3.6421);
+    }
+
+    #[test]
+    fn slack() {
+        // A spring of no frequency does not pull, however it is damped.
+        for damping in [0.0, 0.5, 1.0, 2.0] {
+            let spring = Spring::new(0.0, damping);
+            check_about(spring.progress(3.0), 0.0);
+            let mut follow = Follow::new(1.0, spring);
+            follow.target = 5.0;
+            follow.velocity = 2.0;
+            follow.update(0.5);
+            check_about(follow.value, 2.0);
+            check_about(follow.velocity, 2.0);
+        }
+    }
+
+    #[test]
+    fn follow() {
+        for spring in [
+            Spring::critical(1.5),
+            Spring::new(1.5, 0.4),
+            Spring::new(1.5, 2.0),
+        ] {
+            let mut follow = Follow::new(Vector::ZERO, spring);
+            follow.target = Vector(1.0, 2.0, 3.0);
+            follow.velocity = Vector(0.0, 0.0, -4.0);
+            let mut once = follow;
+            once.update(0.7);
+            for _ in 0..7 {
+                follow.update(0.1);
+            }
+            check_about((follow.value - once.value).len(), 0.0);
+            check_about((follow.velocity - once.velocity).len(), 0.0);
+
+            follow.update(30.0);
+            check_about((follow.value - follow.target).len(), 0.0);
+            check_about(follow.velocity.len(), 0.0);
+        }
+
+        // Letting go from rest follows the progress of the spring.
+        let spring = Spring::new(1.0, 0.5);
+        let mut follow = Follow::new(0.0, spring);
+        follow.target = 1.0;
+        follow.update(0.3);
+        check_about(follow.value, spring.progress(0.3));
+    }
+}
@@ -0,0 +1,257 @@
+//! Raw-mode terminal input through a minimal binding to the C library.
+
+use std::{
+    ffi::{c_int, c_short, c_void},
+    io,
+    mem::MaybeUninit,
+    time::Duration,
+};
+
+// The layout of `struct termios` and the values of its flags differ between systems,
+// and on Linux between architectures, so raw mode is only supported where they have been
+// checked.
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod sys {
+    use std::ffi::{c_uchar, c_uint, c_ulong};
+
+    pub(super) type TcFlag = c_uint;
+    pub(super) type NfdsT = c_ulong;
+
+    /// The layout of `struct termios` on Linux, with glibc or musl.
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub(super) struct Termios {
+        pub(super) c_iflag: TcFlag,
+        pub(super) c_oflag: TcFlag,
+        pub(super) c_cflag: TcFlag,
+        pub(super) c_lflag: TcFlag,
+        c_line: c_uchar,
+        pub(super) c_cc: [c_uchar; 32],
+        c_ispeed: c_uint,
+        c_ospeed: c_uint,
+    }
+
+    pub(super) const ISIG: TcFlag = 0o1;
+    pub(super) const ICANON: TcFlag = 0o2;
+    pub(super) const ECHO: TcFlag = 0o10;
+    pub(super) const IXON: TcFlag = 0o2000;
+    pub(super) const ICRNL: TcFlag = 0o400;
+    pub(super) const VTIME: usize = 5;
+    pub(super) const VMIN: usize = 6;
+    pub(super) const SUPPORTED: bool = true;
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+    use std::ffi::{c_uchar, c_uint, c_ulong};
+
+    pub(super) type TcFlag = c_ulong;
+    pub(super) type NfdsT = c_uint;
+
+    /// The layout of `struct termios` on macOS.
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub(super) struct Termios {
+        pub(super) c_iflag: TcFlag,
+        pub(super) c_oflag: TcFlag,
+        pub(super) c_cflag: TcFlag,
+        pub(super) c_lflag: TcFlag,
+        pub(super) c_cc: [c_uchar; 20],
+        c_ispeed: c_ulong,
+        c_ospeed: c_ulong,
+    }
+
+    pub(super) const ISIG: TcFlag = 0x80;
+    pub(super) const ICANON: TcFlag = 0x100;
+    pub(super) const ECHO: TcFlag = 0x8;
+    pub(super) const IXON: TcFlag = 0x200;
+    pub(super) const ICRNL: TcFlag = 0x100;
+    pub(super) const VTIME: usize = 17;
+    pub(super) const VMIN: usize = 16;
+    pub(super) const SUPPORTED: bool = true;
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+mod sys {
+    use std::ffi::{c_int, c_uchar, c_uint};
+
+    pub(super) type TcFlag = c_uint;
+    pub(super) type NfdsT = c_uint;
+
+    /// The layout of `struct termios` on FreeBSD, OpenBSD and NetBSD.
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub(super) struct Termios {
+        pub(super) c_iflag: TcFlag,
+        pub(super) c_oflag: TcFlag,
+        pub(super) c_cflag: TcFlag,
+        pub(super) c_lflag: TcFlag,
+        pub(super) c_cc: [c_uchar; 20],
+        c_ispeed: c_int,
+        c_ospeed: c_int,
+    }
+
+    pub(super) const ISIG: TcFlag = 0x80;
+    pub(super) const ICANON: TcFlag = 0x100;
+    pub(super) const ECHO: TcFlag = 0x8;
+    pub(super) const IXON: TcFlag = 0x200;
+    pub(super) const ICRNL: TcFlag = 0x100;
+    pub(super) const VTIME: usize = 17;
+    pub(super) const VMIN: usize = 16;
+    pub(super) const SUPPORTED: bool = true;
+}
+
+#[cfg(not(any(
+    all(
+        target_os = "linux",
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ),
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+mod sys {
+    use std::ffi::{c_uchar, c_uint, c_ulong};
+
+    pub(super) type TcFlag = c_uint;
+    pub(super) type NfdsT = c_ulong;
+
+    /// A stand-in for `struct termios` where its layout is unknown, which is never passed
+    /// to the C library since raw mode is not supported there.
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub(super) struct Termios {
+        pub(super) c_iflag: TcFlag,
+        pub(super) c_lflag: TcFlag,
+        pub(super) c_cc: [c_uchar; 32],
+    }
+
+    pub(super) const ISIG: TcFlag = 0;
+    pub(super) const ICANON: TcFlag = 0;
+    pub(super) const ECHO: TcFlag = 0;
+    pub(super) const IXON: TcFlag = 0;
+    pub(super) const ICRNL: TcFlag = 0;
+    pub(super) const VTIME: usize = 0;
+    pub(super) const VMIN: usize = 0;
+    pub(super) const SUPPORTED: bool = false;
+}
+
+use self::sys::{NfdsT, Termios, ECHO, ICANON, ICRNL, ISIG, IXON, SUPPORTED, VMIN, VTIME};
+
+/// The layout of `struct pollfd`.
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+const STDIN: c_int = 0;
+const TCSANOW: c_int = 0;
+const POLLIN: c_short = 1;
+
+extern "C" {
+    fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+    fn tcsetattr(fd: c_int, optional_actions: c_int, termios: *const Termios) -> c_int;
+    fn poll(fds: *mut PollFd, nfds: NfdsT, timeout: c_int) -> c_int;
+    fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+}
+
+/// Standard input switched to raw mode, in which keys are read as they are pressed
+/// without being echoed, until this is dropped.
+pub(crate) struct RawMode {
+    original: Termios,
+}
+
+impl RawMode {
+    /// Switches standard input to raw mode, failing if it is not a terminal or raw mode
+    /// is not supported on this system.
+    pub(crate) fn enable() -> io::Result<Self> {
+        if !SUPPORTED {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "raw terminal input is not supported on this system",
+            ));
+        }
+        let mut termios = MaybeUninit::<Termios>::uninit();
+        // SAFETY: `tcgetattr` fills in the whole `termios` when it succeeds.
+        let original = unsafe {
+            if tcgetattr(STDIN, termios.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            termios.assume_init()
+        };
+
+        let mut raw = original;
+        // Signals are turned off too, so that Ctrl-C is read as a key and the terminal is
+        // restored before quitting.
+        raw.c_lflag &= !(ICANON | ECHO | ISIG);
+        raw.c_iflag &= !(IXON | ICRNL);
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+        // SAFETY: `raw` is a valid `termios` obtained from `tcgetattr`.
+        if unsafe { tcsetattr(STDIN, TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { original })
+    }
+
+    /// Waits up to `timeout` for input and returns the bytes that are available, which
+    /// are none if the timeout passed first.
+    pub(crate) fn read(&self, timeout: Duration) -> io::Result<Vec<u8>> {
+        let mut fd = PollFd {
+            fd: STDIN,
+            events: POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.as_millis().try_into().unwrap_or(c_int::MAX);
+        // SAFETY: `fd` is a single valid `pollfd`.
+        match unsafe { poll(&mut fd, 1, timeout) } {
+            0 => return Ok(Vec::new()),
+            n if n < 0 => {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::Interrupted => Ok(Vec::new()),
+                    _ => Err(error),
+                };
+            }
+            _ => {}
+        }
+
+        // Standard input is read directly, as the buffering of `io::Stdin` would hide
+        // bytes from `poll`.
+        let mut buf = vec![0; 64];
+        // SAFETY: `buf` is valid for writes of its length.
+        let n = unsafe { read(STDIN, buf.as_mut_ptr().cast(), buf.len()) };
+        if n < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        buf.truncate(n as usize);
+        Ok(buf)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: `original` is a valid `termios` obtained from `tcgetattr`.
+        unsafe {
+            tcsetattr(STDIN, TCSANOW, &self.original);
+        }
+    }
+}
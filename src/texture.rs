@@ -0,0 +1,374 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{Animate, Color, Easing, GradientNoise, Hit, Image};
+
+/// The number of layers of noise that noisy textures are made of.
+const OCTAVES: u32 = 4;
+
+/// A pattern of colors across the surfaces of objects, applied by
+/// [`HittableExt::texture`](crate::HittableExt::texture).
+///
+/// Textures are mapped by the [`uv`](Hit::uv) coordinates of hits across surfaces, or
+/// by the [`point`](Hit::point) hit for solid textures that run through objects.
+pub trait Texture {
+    /// Returns the color of this texture at `hit`.
+    fn color(&self, hit: &Hit) -> Color;
+}
+
+impl<F: Fn(&Hit) -> Color> Texture for F {
+    fn color(&self, hit: &Hit) -> Color {
+        self(hit)
+    }
+}
+
+/// A checkerboard of squares of two colors.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Checker {
+    /// The colors of the squares, starting at the corner where u and v are 0.
+    pub colors: [Color; 2],
+    /// How many squares there are across u and v.
+    pub size: (f64, f64),
+}
+
+impl Texture for Checker {
+    fn color(&self, hit: &Hit) -> Color {
+        let (u, v) = hit.uv;
+        let square = (u * self.size.0).floor() + (v * self.size.1).floor();
+        self.colors[square.rem_euclid(2.0) as usize]
+    }
+}
+
+/// Stripes of two colors, one after the other across u.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stripes {
+    /// The colors of the stripes, starting where u is 0.
+    pub colors: [Color; 2],
+    /// How many pairs of stripes there are across u.
+    pub count: f64,
+}
+
+impl Texture for Stripes {
+    fn color(&self, hit: &Hit) -> Color {
+        let stripe = (2.0 * hit.uv.0 * self.count).floor();
+        self.colors[stripe.rem_euclid(2.0) as usize]
+    }
+}
+
+/// A blend from one color to another across v.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Gradient {
+    /// The color where v is 0.
+    pub from: Color,
+    /// The color where v is 1.
+    pub to: Color,
+    /// How the colors blend in between.
+    pub easing: Easing,
+}
+
+impl Texture for Gradient {
+    fn color(&self, hit: &Hit) -> Color {
+        let v = hit.uv.1.clamp(0.0, 1.0);
+        self.from.lerp(self.to, self.easing.ease(v))
+    }
+}
+
+/// A solid texture of cloudy blotches blending between two colors, running through
+/// objects by the points hit rather than across their surfaces.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Noise {
+    /// The colors blended between.
+    pub colors: [Color; 2],
+    /// The size of the blotches.
+    pub scale: f64,
+    /// The seed the blotches are laid out by, each seed giving a different layout.
+    pub seed: u64,
+}
+
+impl Texture for Noise {
+    fn color(&self, hit: &Hit) -> Color {
+        let noise = GradientNoise::new(self.seed).fbm(hit.point / self.scale, OCTAVES);
+        self.colors[0].lerp(self.colors[1], (0.5 + noise).clamp(0.0, 1.0))
+    }
+}
+
+/// A solid texture of veins of one color through another, running along x.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Marble {
+    /// The color of the stone, and of its veins.
+    pub colors: [Color; 2],
+    /// The distance between veins.
+    pub scale: f64,
+    /// The seed the veins are twisted by.
+    pub seed: u64,
+}
+
+impl Texture for Marble {
+    fn color(&self, hit: &Hit) -> Color {
+        let point = hit.point / self.scale;
+        let turbulence = GradientNoise::new(self.seed).turbulence(point, OCTAVES);
+        let vein = (PI * (point.x() + 4.0 * turbulence)).sin().abs();
+        self.colors[1].lerp(self.colors[0], vein.sqrt())
+    }
+}
+
+/// A solid texture of the growth rings of wood, around the z-axis.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Wood {
+    /// The colors that the rings blend from and to.
+    pub colors: [Color; 2],
+    /// The distance between rings.
+    pub scale: f64,
+    /// The seed the rings are warped by.
+    pub seed: u64,
+}
+
+impl Texture for Wood {
+    fn color(&self, hit: &Hit) -> Color {
+        let point = hit.point / self.scale;
+        let warp = GradientNoise::new(self.seed).fbm(point * 0.5, OCTAVES);
+        let ring = (point.x().hypot(point.y()) + warp).rem_euclid(1.0);
+        self.colors[0].lerp(self.colors[1], ring)
+    }
+}
+
+/// How an [`ImageTexture`] picks the color between the centers of pixels.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Filter {
+    /// The color of the closest pixel, for crisp pixel art.
+    Nearest,
+    /// A blend of the four closest pixels, for smooth images.
+    #[default]
+    Bilinear,
+}
+
+/// What an [`ImageTexture`] shows outside of its image, where u or v are not from 0 to 1.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Wrap {
+    /// The image again, tiled.
+    #[default]
+    Repeat,
+    /// The pixels at the edges of the image, stretched out.
+    Clamp,
+}
+
+impl Wrap {
+    /// Returns the pixel at `index` along a side of the image `size` pixels long.
+    fn pixel(self, index: f64, size: usize) -> usize {
+        match self {
+            Self::Repeat => index.rem_euclid(size as f64) as usize % size,
+            Self::Clamp => index.clamp(0.0, size as f64 - 1.0) as usize,
+        }
+    }
+}
+
+/// An image wrapped onto surfaces, such as a logo on the sides of a cube.
+///
+/// The image is mapped with u from its left to its right and v from its bottom to its
+/// top.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImageTexture {
+    /// The image, which is shared rather than copied by clones of the texture.
+    pub image: Arc<Image>,
+    /// How the color between the centers of pixels is picked.
+    pub filter: Filter,
+    /// What is shown outside of the image.
+    pub wrap: Wrap,
+}
+
+impl ImageTexture {
+    /// Returns a texture of `image`, filtered bilinearly and repeated outside of it.
+    pub fn new(image: impl Into<Arc<Image>>) -> Self {
+        Self {
+            image: image.into(),
+            filter: Filter::default(),
+            wrap: Wrap::default(),
+        }
+    }
+
+    /// Returns the pixel at column `x` and row `y`, wrapped around the image.
+    fn pixel(&self, x: f64, y: f64) -> Color {
+        let (width, height) = (self.image.width(), self.image.height());
+        let (x, y) = (self.wrap.pixel(x, width), self.wrap.pixel(y, height));
+        self.image.get(x, y).copied().unwrap_or(Color::BLACK)
+    }
+
+    /// Returns the color of the image at `(u, v)`, from its bottom-left.
+    pub(crate) fn sample(&self, (u, v): (f64, f64)) -> Color {
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return Color::BLACK;
+        }
+        let x = u * self.image.width() as f64;
+        let y = (1.0 - v) * self.image.height() as f64;
+        match self.filter {
+            Filter::Nearest => self.pixel(x.floor(), y.floor()),
+            Filter::Bilinear => {
+                // Measured from the centers of pixels.
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (left, top) = (x.floor(), y.floor());
+                let (sx, sy) = (x - left, y - top);
+                let row = |y| self.pixel(left, y).lerp(self.pixel(left + 1.0, y), sx);
+                row(top).lerp(row(top + 1.0), sy)
+            }
+        }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn color(&self, hit: &Hit) -> Color {
+        self.sample(hit.uv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Checker, Filter, Gradient, ImageTexture, Marble, Noise, Stripes, Texture, Wood, Wrap,
+    };
+    use crate::{
+        cube, util::check_about, Color, Easing, Hit, Hittable, HittableExt, Image, Ray, Torus,
+        Translation, Vector,
+    };
+
+    fn at(uv: (f64, f64), point: Vector) -> Hit {
+        Hit {
+            uv,
+            point,
+            ..Hit::new(Color::BLACK, 1.0)
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        let checker = Checker {
+            colors: [Color::BLACK, Color::WHITE],
+            size: (4.0, 2.0),
+        };
+        assert_eq!(checker.color(&at((0.1, 0.1), Vector::ZERO)), Color::BLACK);
+        assert_eq!(checker.color(&at((0.3, 0.1), Vector::ZERO)), Color::WHITE);
+        assert_eq!(checker.color(&at((0.3, 0.6), Vector::ZERO)), Color::BLACK);
+        assert_eq!(checker.color(&at((-0.1, 0.1), Vector::ZERO)), Color::WHITE);
+
+        let stripes = Stripes {
+            colors: [Color::RED, Color::BLUE],
+            count: 2.0,
+        };
+        assert_eq!(stripes.color(&at((0.2, 0.9), Vector::ZERO)), Color::RED);
+        assert_eq!(stripes.color(&at((0.3, 0.9), Vector::ZERO)), Color::BLUE);
+
+        let gradient = Gradient {
+            from: Color::BLACK,
+            to: Color::WHITE,
+            easing: Easing::Linear,
+        };
+        check_about(gradient.color(&at((0.0, 0.25), Vector::ZERO)).0, 0.25);
+        assert_eq!(gradient.color(&at((0.0, 2.0), Vector::ZERO)), Color::WHITE);
+    }
+
+    #[test]
+    fn solid() {
+        let colors = [Color::BLACK, Color::WHITE];
+        let textures: [&dyn Texture; 3] = [
+            &Noise {
+                colors,
+                scale: 0.5,
+                seed: 3,
+            },
+            &Marble {
+                colors,
+                scale: 0.5,
+                seed: 3,
+            },
+            &Wood {
+                colors,
+                scale: 0.1,
+                seed: 3,
+            },
+        ];
+        for texture in textures {
+            let values: Vec<_> = (0..100)
+                .map(|i| {
+                    texture
+                        .color(&at((0.0, 0.0), Vector(i as f64 * 0.037, -0.2, 0.7)))
+                        .0
+                })
+                .collect();
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+            assert!(values.iter().any(|&value| (value - values[0]).abs() > 0.2));
+        }
+        // Solid textures are the same anywhere on a surface, wherever it is mapped.
+        let noise = textures[0];
+        let point = Vector(0.3, 0.3, 0.3);
+        assert_eq!(
+            noise.color(&at((0.0, 0.0), point)),
+            noise.color(&at((0.5, 0.5), point))
+        );
+    }
+
+    #[test]
+    fn image() {
+        let mut image = Image::new(2, 2, Color::BLACK);
+        *image.get_mut(1, 0).unwrap() = Color::WHITE;
+        *image.get_mut(0, 1).unwrap() = Color::RED;
+        let mut texture = ImageTexture::new(image);
+        // The top-right pixel is at the top of v.
+        texture.filter = Filter::Nearest;
+        assert_eq!(texture.color(&at((0.9, 0.9), Vector::ZERO)), Color::WHITE);
+        assert_eq!(texture.color(&at((0.1, 0.1), Vector::ZERO)), Color::RED);
+        assert_eq!(texture.color(&at((1.1, -0.9), Vector::ZERO)), Color::RED);
+        texture.wrap = Wrap::Clamp;
+        assert_eq!(texture.color(&at((1.1, -0.9), Vector::ZERO)), Color::BLACK);
+
+        // Halfway between the pixel centers is a blend of all four.
+        texture.filter = Filter::Bilinear;
+        let color = texture.color(&at((0.5, 0.5), Vector::ZERO));
+        check_about(color.0, 0.5);
+        check_about(color.1, 0.25);
+        assert_eq!(texture.color(&at((0.75, 0.75), Vector::ZERO)), Color::WHITE);
+        texture.wrap = Wrap::Repeat;
+        let color = texture.color(&at((1.0, 0.25), Vector::ZERO));
+        check_about(color.0, 0.5);
+        check_about(color.1, 0.0);
+    }
+
+    #[test]
+    fn surfaces() {
+        let incidence = Ray {
+            origin: Vector::ZERO,
+            direction: Vector(0.1, 1.0, 0.2),
+        };
+        // The side of the cube facing the camera is mapped across x and z.
+        let hit = cube()
+            .transform(Translation(Vector(0.0, 2.5, 0.0)))
+            .hit(incidence)
+            .unwrap();
+        check_about(hit.uv.0, 0.7);
+        check_about(hit.uv.1, 0.9);
+        check_about((hit.point - Vector(0.2, -0.5, 0.4)).len(), 0.0);
+
+        // The torus is mapped around its center and then around its tube, starting from
+        // the outside of the tube.
+        let torus = Torus {
+            radius_major: 1.0,
+            radius_minor: 0.25,
+        }
+        .transform(Translation(Vector(0.0, 2.0, 0.0)));
+        let hit = torus
+            .hit(Ray {
+                origin: Vector(-1.0, 0.0, 0.0),
+                direction: Vector(0.0, 1.0, 0.0),
+            })
+            .unwrap();
+        check_about(hit.uv.0, 0.5);
+        check_about(hit.uv.1, 0.75);
+
+        let textured = torus.texture(|hit: &Hit| Color(hit.uv.0, hit.uv.1, 0.0));
+        let hit = textured
+            .hit(Ray {
+                origin: Vector(0.0, 0.0, 1.1),
+                direction: Vector(0.0, 1.0, 0.0),
+            })
+            .unwrap();
+        check_about(hit.color.0, 0.25);
+        check_about(hit.color.1, 0.8155);
+    }
+}
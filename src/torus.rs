@@ -1,10 +1,14 @@
-use crate::{hit::Hittable, Color, Hit, Ray};
+use std::f64::consts::TAU;
 
-/// A [`Hittable`] torus.
-#[derive(Copy, Clone, Debug)]
-pub(crate) struct Torus {
-    pub(crate) radius_major: f64,
-    pub(crate) radius_minor: f64,
+use crate::{hit::Hittable, Color, Hit, Ray, Vector};
+
+/// A [`Hittable`] torus centered on the origin, around the y-axis.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Torus {
+    /// The distance from the center of the torus to the center of its tube.
+    pub radius_major: f64,
+    /// The radius of the tube.
+    pub radius_minor: f64,
 }
 
 impl Hittable for Torus {
@@ -29,9 +33,24 @@ impl Hittable for Torus {
             .filter(|n| n.is_finite() && *n >= 0.0)
             .reduce(f64::min);
 
-        solution.map(|t| Hit {
-            color: Color::White,
-            t,
+        solution.map(|t| {
+            let point = incidence.at(t);
+            // The angle around the y-axis, and the angle around the tube from its outside.
+            let around = point.z().atan2(point.x());
+            let ring = (point.x().powi(2) + point.z().powi(2)).sqrt() - self.radius_major;
+            let tube = point.y().atan2(ring);
+            // Out from the circle through the middle of the tube.
+            let center = Vector(point.x(), 0.0, point.z());
+            let normal = match center.len() {
+                len if len > 0.0 => point - center * (self.radius_major / len),
+                _ => Vector(0.0, point.y(), 0.0),
+            };
+            Hit {
+                normal: normal.normalized(),
+                uv: ((around / TAU).rem_euclid(1.0), (tube / TAU).rem_euclid(1.0)),
+                point,
+                ..Hit::new(Color::WHITE, t)
+            }
         })
     }
 }
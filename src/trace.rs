@@ -0,0 +1,209 @@
+use std::{num::NonZeroUsize, thread};
+
+use crate::{
+    random::Random, view::in_rows, Cell, Color, Framebuffer, Hittable, ObjectId, Viewport,
+};
+
+/// What the samples of a cell of a [`PathTracer`] have added up to so far.
+#[derive(Copy, Clone, Debug)]
+struct Samples {
+    /// The sum of the colors of the samples that hit something.
+    sum: Color,
+    /// The number of samples that hit something.
+    hits: usize,
+    /// The distance from the camera to the closest hit, or infinity if nothing was hit.
+    depth: f64,
+    /// The object of the closest hit.
+    object: Option<ObjectId>,
+}
+
+impl Samples {
+    /// The samples of a cell before any are taken.
+    const NONE: Self = Self {
+        sum: Color::BLACK,
+        hits: 0,
+        depth: f64::INFINITY,
+        object: None,
+    };
+}
+
+/// Renders a [path traced](crate::HittableExt::path_traced) scene bit by bit, adding a
+/// sample to every cell of a [`Viewport`] with each pass, so that its image starts out
+/// noisy and refines for as long as the scene stays the same.
+///
+/// Each sample is cast through a random point of its cell, which smooths the edges of
+/// objects as well, so the [`sampling`](Viewport::sampling) of the viewport is not used.
+/// The image is the same for the same seed and number of samples every time, on any
+/// number of threads.
+#[derive(Clone, Debug)]
+pub struct PathTracer {
+    viewport: Viewport,
+    seed: u64,
+    samples: usize,
+    cells: Vec<Samples>,
+}
+
+impl PathTracer {
+    /// Returns a path tracer of the cells of `viewport` with no samples yet, whose
+    /// samples are laid out by `seed`.
+    pub fn new(viewport: Viewport, seed: u64) -> Self {
+        Self {
+            viewport,
+            seed,
+            samples: 0,
+            cells: vec![Samples::NONE; viewport.width * viewport.height],
+        }
+    }
+
+    /// Returns the number of samples taken of every cell so far.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Forgets the samples taken so far, to start over on a scene that has changed.
+    pub fn reset(&mut self) {
+        self.samples = 0;
+        self.cells.fill(Samples::NONE);
+    }
+
+    /// Adds a sample of `scene` to every cell, on as many threads as are available.
+    pub fn sample(&mut self, scene: impl Hittable + Sync) {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.sample_with_threads(scene, threads);
+    }
+
+    /// Adds a sample of `scene` to every cell like [`sample`](Self::sample), with rows
+    /// shared out between `threads` threads, or traced on the current thread if
+    /// `threads` is 1.
+    pub fn sample_with_threads(&mut self, scene: impl Hittable + Sync, threads: usize) {
+        let Self {
+            viewport,
+            seed,
+            samples,
+            ..
+        } = *self;
+        let rows = self.cells.chunks_mut(viewport.width.max(1));
+        in_rows(rows, threads, |y, row| {
+            let j = viewport.height - 1 - y;
+            for (i, cell) in row.iter_mut().enumerate() {
+                // Seeding by the cell and the pass keeps every sample different, and the
+                // same no matter which thread takes it.
+                let index = (y * viewport.width + i) as u64;
+                let mut random = Random::from_values([seed, samples as u64, index]);
+                let u = (i as f64 + random.next_f64()) / viewport.width as f64;
+                let v = (j as f64 + random.next_f64()) / viewport.height as f64;
+                if let Some(hit) = viewport.cast(&scene, u, v) {
+                    cell.sum += hit.color;
+                    cell.hits += 1;
+                    if hit.t < cell.depth {
+                        cell.depth = hit.t;
+                        cell.object = hit.object;
+                    }
+                }
+            }
+        });
+        self.samples += 1;
+    }
+
+    /// Returns the image so far, with each cell the average of the samples that hit
+    /// something, and empty until a sample has been taken.
+    pub fn framebuffer(&self) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(self.viewport.width, self.viewport.height);
+        let cells = framebuffer.rows_mut().flatten();
+        for (cell, samples) in cells.zip(&self.cells) {
+            if samples.hits > 0 {
+                *cell = Cell {
+                    color: Some(samples.sum / samples.hits as f64),
+                    depth: samples.depth,
+                    object: samples.object,
+                    missed: (self.samples - samples.hits) as f64 / self.samples as f64,
+                };
+            }
+        }
+        framebuffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathTracer;
+    use crate::{
+        cube, util::check_about, Camera, Color, HittableExt, Lighting, Material, Matrix,
+        Translation, Vector, Viewport,
+    };
+
+    /// Returns lighting by nothing but a sky of `sky`.
+    fn sky(sky: Color) -> Lighting {
+        Lighting {
+            background: sky,
+            ..Lighting::new(Vec::new())
+        }
+    }
+
+    #[test]
+    fn converges() {
+        // A cube under a white sky, with nothing to shade it, is as bright as its color.
+        let scene = cube()
+            .colorize(Color(0.5, 0.5, 0.5))
+            .transform(Translation(Vector(0.0, 3.0, 0.0)))
+            .path_traced(sky(Color::WHITE));
+        let viewport = Viewport::new(3, 3, Camera::new(0.5, 0.2, 0.2));
+        let mut tracer = PathTracer::new(viewport, 1);
+        assert_eq!(tracer.framebuffer().get(1, 1).unwrap().color, None);
+        for _ in 0..8 {
+            tracer.sample(&scene);
+        }
+        assert_eq!(tracer.samples(), 8);
+        let cell = *tracer.framebuffer().get(1, 1).unwrap();
+        check_about(cell.color.unwrap().0, 0.5);
+        check_about(cell.depth, 2.5);
+        check_about(cell.coverage(), 1.0);
+        tracer.reset();
+        assert_eq!(tracer.samples(), 0);
+        assert_eq!(tracer.framebuffer().get(1, 1).unwrap().color, None);
+    }
+
+    #[test]
+    fn area_light() {
+        // A white floor in the dark is lit only by a glowing panel above it.
+        let floor = cube()
+            .colorize(Color::WHITE)
+            .transform(Matrix::scale(Vector(4.0, 4.0, 0.1)))
+            .transform(Translation(Vector(0.0, 3.0, -1.0)));
+        let lamp = cube()
+            .material(Material {
+                diffuse: 0.0,
+                ..Material::emissive(Color::WHITE)
+            })
+            .transform(Matrix::scale(Vector(6.0, 6.0, 0.1)))
+            .transform(Translation(Vector(0.0, 3.0, 1.5)));
+        let scene = floor.and(lamp).path_traced(sky(Color::BLACK));
+        let viewport = Viewport::new(8, 8, Camera::new(0.5, 1.0, 1.0));
+        let mut tracer = PathTracer::new(viewport, 7);
+        for _ in 0..16 {
+            tracer.sample(&scene);
+        }
+        let framebuffer = tracer.framebuffer();
+        // The bottom of the lamp glows as brightly as it gives off light.
+        assert_eq!(framebuffer.get(4, 1).unwrap().color, Some(Color::WHITE));
+        // The floor below is lit by some of it, but not all.
+        let floor = framebuffer.get(4, 6).unwrap().color.unwrap();
+        assert!(floor.0 > 0.2 && floor.0 < 0.9, "{:?}", floor);
+    }
+
+    #[test]
+    fn reproducible() {
+        let scene = crate::scene::demo(0.4).path_traced(sky(Color::WHITE));
+        let viewport = Viewport::new(23, 11, Camera::new(0.5, 1.5, 1.0));
+        let render = |seed, threads| {
+            let mut tracer = PathTracer::new(viewport, seed);
+            for _ in 0..3 {
+                tracer.sample_with_threads(&scene, threads);
+            }
+            tracer.framebuffer()
+        };
+        let serial = render(3, 1);
+        assert_eq!(render(3, 4), serial);
+        assert_ne!(render(4, 1), serial);
+    }
+}
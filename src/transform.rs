@@ -1,14 +1,20 @@
 use crate::{Ray, Vector};
 
-pub(crate) trait Transformation {
+/// A transformation of points in space, such as a [`Matrix`](crate::Matrix) or a
+/// [`Translation`](crate::Translation).
+pub trait Transformation {
+    /// Returns `vector` transformed.
     fn transform(&self, vector: Vector) -> Vector;
 
+    /// Returns the transformation that undoes this one.
     fn inverse(&self) -> Self
     where
         Self: Sized;
 }
 
-pub(crate) trait TransformationExt: Transformation {
+/// Extension utilities implemented for all [`Transformation`]s.
+pub trait TransformationExt: Transformation {
+    /// Returns `ray` with its origin and a point along it transformed.
     fn transform_ray(&self, ray: Ray) -> Ray {
         let origin = self.transform(ray.origin);
         let direction = self.transform(ray.origin + ray.direction) - origin;
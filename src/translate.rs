@@ -1,7 +1,7 @@
 use crate::{Transformation, Vector};
 
 #[derive(Copy, Clone, Debug)]
-pub(crate) struct Translation(pub(crate) Vector);
+pub struct Translation(pub Vector);
 
 impl Transformation for Translation {
     fn transform(&self, vector: Vector) -> Vector {
@@ -1,50 +1,64 @@
 use std::ops;
 
 /// A 3D vector of [`f64`]s.
-#[derive(Copy, Clone, Default, Debug)]
-pub(crate) struct Vector(
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct Vector(
     /// The x-coordinate of this vector.
-    pub(crate) f64,
+    pub f64,
     /// The y-coordinate of this vector.
-    pub(crate) f64,
+    pub f64,
     /// The z-coordinate of this vector.
-    pub(crate) f64,
+    pub f64,
 );
 
 impl Vector {
     /// A vector with zero values.
-    pub(crate) const ZERO: Self = Self(0.0, 0.0, 0.0);
+    pub const ZERO: Self = Self(0.0, 0.0, 0.0);
 
     /// Returns the x-coordinate of this vector.
-    pub(crate) const fn x(self) -> f64 {
+    pub const fn x(self) -> f64 {
         self.0
     }
 
     /// Returns the y-coordinate of this vector.
-    pub(crate) const fn y(self) -> f64 {
+    pub const fn y(self) -> f64 {
         self.1
     }
 
     /// Returns the z-coordinate of this vector.
-    pub(crate) const fn z(self) -> f64 {
+    pub const fn z(self) -> f64 {
         self.2
     }
 
     /// Returns the dot product of this vector with another vector
-    pub(crate) fn dot(self, rhs: Vector) -> f64 {
+    pub fn dot(self, rhs: Vector) -> f64 {
         self.0 * rhs.0 + self.1 * rhs.1 + self.2 * rhs.2
     }
 
     /// Returns the length of this vector, squared.
-    pub(crate) fn len_squared(self) -> f64 {
+    pub fn len_squared(self) -> f64 {
         self.dot(self)
     }
 
     /// Returns the length of this vector
-    #[allow(dead_code)]
-    pub(crate) fn len(self) -> f64 {
+    pub fn len(self) -> f64 {
         self.len_squared().sqrt()
     }
+
+    /// Returns the cross product of this vector with another vector, which is at right
+    /// angles to both.
+    pub fn cross(self, rhs: Vector) -> Vector {
+        Self(
+            self.1 * rhs.2 - self.2 * rhs.1,
+            self.2 * rhs.0 - self.0 * rhs.2,
+            self.0 * rhs.1 - self.1 * rhs.0,
+        )
+    }
+
+    /// Returns this vector scaled to a length of 1.
+    pub fn normalized(self) -> Vector {
+        self / self.len()
+    }
 }
 
 impl ops::Add for Vector {
@@ -142,4 +156,16 @@ mod tests {
         check_about(b.y(), -8.0);
         check_about(b.z(), -9.3);
     }
+
+    #[test]
+    fn cross() {
+        let a = Vector(1.0, 2.0, 3.0);
+        let b = Vector(-2.0, 0.5, 4.0);
+        let c = a.cross(b);
+        check_about(c.x(), 6.5);
+        check_about(c.y(), -10.0);
+        check_about(c.z(), 4.5);
+        check_about(c.dot(a), 0.0);
+        check_about(Vector(3.0, 0.0, 4.0).normalized().z(), 0.8);
+    }
 }
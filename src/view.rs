@@ -1,50 +1,294 @@
-use std::io;
+use std::{
+    num::NonZeroUsize,
+    sync::{Mutex, PoisonError},
+    thread,
+};
 
-use crate::{Color, Hittable, Ray, Vector};
+use crate::{random::Random, Cell, Framebuffer, Hit, Hittable, Ray, Vector};
 
 /// A camera in a 3D world.
 ///
 /// The focal point is always located at the origin, and the camera is always
 /// pointing forward in the y+ direction, with z+ above and x+ to the right.
-pub(crate) struct Camera {
-    pub(crate) focal_len: f64,
-    pub(crate) width: f64,
-    pub(crate) height: f64,
+#[derive(Copy, Clone, Debug)]
+pub struct Camera {
+    /// The distance from the focal point to the image plane.
+    pub focal_len: f64,
+    /// The width of the image plane.
+    pub width: f64,
+    /// The height of the image plane.
+    pub height: f64,
 }
 
 impl Camera {
-    pub(crate) fn project(&self, scene: impl Hittable, u: f64, v: f64) -> Option<Color> {
-        let incidence = Ray {
+    /// Returns a camera with an image plane of `width` by `height` at `focal_len` from
+    /// the focal point.
+    pub fn new(focal_len: f64, width: f64, height: f64) -> Self {
+        Self {
+            focal_len,
+            width,
+            height,
+        }
+    }
+
+    /// Casts a ray through the point of the image plane at `u` from the left and `v`
+    /// from the bottom, both between 0 and 1, and returns what it hits in `scene`.
+    pub fn project(&self, scene: impl Hittable, u: f64, v: f64) -> Option<Hit> {
+        scene.hit(self.ray(u, v))
+    }
+
+    /// Returns the ray from the focal point through the point of the image plane at `u`
+    /// from the left and `v` from the bottom.
+    fn ray(&self, u: f64, v: f64) -> Ray {
+        Ray {
             origin: Vector::ZERO,
             direction: Vector(
                 -self.width / 2.0 + u * self.width,
                 self.focal_len,
                 -self.height / 2.0 + v * self.height,
             ),
-        };
-        scene.hit(incidence).map(|hit| hit.color)
+        }
+    }
+}
+
+/// Where within each cell of a [`Viewport`] rays are cast.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Sampling {
+    /// A single ray through the center of the cell.
+    #[default]
+    Center,
+    /// A ray through the center of each part of the cell divided into a grid of
+    /// `columns` by `rows`.
+    Grid { columns: usize, rows: usize },
+    /// A ray through a random point of each part of the cell divided into a grid of
+    /// `columns` by `rows`, which trades the regular stepping of edges for noise.
+    ///
+    /// The points are the same for a cell every time it is rasterized.
+    Jittered { columns: usize, rows: usize },
+}
+
+impl Sampling {
+    /// Returns the number of columns and rows of samples in each cell.
+    fn grid(self) -> (usize, usize) {
+        match self {
+            Self::Center => (1, 1),
+            Self::Grid { columns, rows } | Self::Jittered { columns, rows } => {
+                (columns.max(1), rows.max(1))
+            }
+        }
     }
 }
 
-pub(crate) struct Viewport {
-    pub(crate) width: usize,
-    pub(crate) height: usize,
-    pub(crate) camera: Camera,
+/// A grid of cells seen through a [`Camera`].
+///
+/// More settings may be added to viewports over time, so outside of this crate they are
+/// created with [`Viewport::new`].
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct Viewport {
+    /// The number of columns of cells.
+    pub width: usize,
+    /// The number of rows of cells.
+    pub height: usize,
+    /// The camera whose image plane is divided into cells.
+    pub camera: Camera,
+    /// Where within each cell rays are cast.
+    pub sampling: Sampling,
+    /// Whether the [background](crate::HittableExt::background) of the scene is shown,
+    /// or left out as if nothing was hit, to draw the scene over something else.
+    pub background: bool,
 }
 
 impl Viewport {
-    pub(crate) fn render(&self, mut buf: impl io::Write, scene: impl Hittable) -> io::Result<()> {
-        for j in (0..self.height).rev() {
+    /// Returns a viewport of `width` by `height` cells seen through `camera`, with a
+    /// single ray through the center of each cell, showing the background.
+    pub fn new(width: usize, height: usize, camera: Camera) -> Self {
+        Self {
+            width,
+            height,
+            camera,
+            sampling: Sampling::Center,
+            background: true,
+        }
+    }
+
+    /// Casts a ray through the point of the image plane at `u` from the left and `v`
+    /// from the bottom, and returns what it hits in `scene`, unless that is a background
+    /// this viewport leaves out.
+    ///
+    /// The `t` of the hit is its distance from the camera rather than along the ray, for
+    /// the [depth](Cell::depth) of the cell it lands in.
+    pub(crate) fn cast(&self, scene: impl Hittable, u: f64, v: f64) -> Option<Hit> {
+        let incidence = self.camera.ray(u, v);
+        let hit = scene.hit(incidence)?;
+        if !self.background && !hit.t.is_finite() {
+            return None;
+        }
+        Some(Hit {
+            t: hit.t * incidence.direction.len(),
+            ..hit
+        })
+    }
+
+    /// Renders `scene` into a [`Framebuffer`] with a [`Cell`] for each ray cast through
+    /// this viewport, on as many threads as are available.
+    pub fn rasterize(&self, scene: impl Hittable + Sync) -> Framebuffer {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.rasterize_with_threads(scene, threads)
+    }
+
+    /// Renders `scene` like [`rasterize`](Self::rasterize), with rows shared out between
+    /// `threads` threads, or traced on the current thread if `threads` is 1.
+    ///
+    /// The framebuffer is the same regardless of the number of threads.
+    pub fn rasterize_with_threads(
+        &self,
+        scene: impl Hittable + Sync,
+        threads: usize,
+    ) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(self.width, self.height);
+        in_rows(framebuffer.rows_mut(), threads, |y, row| {
+            self.rasterize_row(&scene, y, row)
+        });
+        framebuffer
+    }
+
+    /// Renders the `y`th row of cells from the top into `row`.
+    fn rasterize_row(&self, scene: &impl Hittable, y: usize, row: &mut [Cell]) {
+        let j = self.height - 1 - y;
+        if self.sampling == Sampling::Center {
             let v = (j as f64 + 0.5) / self.height as f64;
-            for i in 0..self.width {
+            for (i, cell) in row.iter_mut().enumerate() {
                 let u = (i as f64 + 0.5) / self.width as f64;
-                match self.camera.project(&scene, u, v) {
-                    Some(color) => write!(buf, "{}█", color)?,
-                    None => write!(buf, " ")?,
+                *cell = Cell::from(self.cast(scene, u, v));
+            }
+            return;
+        }
+
+        let (columns, rows) = self.sampling.grid();
+        for (i, cell) in row.iter_mut().enumerate() {
+            // Seeding by the position of the cell keeps jittered rasterizations the same
+            // no matter which thread renders the row.
+            let mut random = Random::new((y * self.width + i) as u64);
+            let mut offset = || match self.sampling {
+                Sampling::Jittered { .. } => random.next_f64(),
+                Sampling::Center | Sampling::Grid { .. } => 0.5,
+            };
+            let samples = (0..rows).flat_map(|row| (0..columns).map(move |column| (column, row)));
+            *cell = Cell::from_samples(samples.map(|(column, row)| {
+                let u =
+                    (i as f64 + (column as f64 + offset()) / columns as f64) / self.width as f64;
+                let v = (j as f64 + (row as f64 + offset()) / rows as f64) / self.height as f64;
+                self.cast(scene, u, v)
+            }));
+        }
+    }
+}
+
+/// Calls `render` with each of `rows` and how far from the top it is, with the rows
+/// shared out between `threads` threads, or on the current thread if `threads` is 1.
+pub(crate) fn in_rows<'a, T: Send + 'a>(
+    rows: impl IntoIterator<Item = &'a mut [T]>,
+    threads: usize,
+    render: impl Fn(usize, &mut [T]) + Sync,
+) {
+    let rows: Vec<_> = rows.into_iter().collect();
+    let threads = threads.clamp(1, rows.len().max(1));
+    if threads == 1 {
+        for (y, row) in rows.into_iter().enumerate() {
+            render(y, row);
+        }
+        return;
+    }
+
+    // Rows are handed out one at a time, so threads that get rows with little in them
+    // go on to take more.
+    let rows = Mutex::new(rows.into_iter().enumerate());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let next = rows.lock().unwrap_or_else(PoisonError::into_inner).next();
+                match next {
+                    Some((y, row)) => render(y, row),
+                    None => break,
                 }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Camera, Sampling, Viewport};
+    use crate::{cube, util::check_about, Cell, Color, HittableExt, Translation, Vector};
+
+    #[test]
+    fn rasterize() {
+        let viewport = Viewport::new(3, 3, Camera::new(0.5, 1.0, 1.0));
+        let framebuffer = viewport.rasterize(cube().transform(Translation(Vector(0.0, 3.0, 0.0))));
+        let center = framebuffer.get(1, 1).unwrap();
+        assert_eq!(center.color, Some(Color::RED));
+        // The front of the cube is 2.5 away, though 5 along the ray through the center.
+        check_about(center.depth, 2.5);
+        assert_eq!(framebuffer.get(0, 0).unwrap().color, None);
+    }
+
+    #[test]
+    fn threads() {
+        let mut viewport = Viewport::new(37, 23, Camera::new(0.5, 1.5, 1.0));
+        let scene = crate::scene::demo(1.3);
+        for sampling in [
+            Sampling::Center,
+            Sampling::Jittered {
+                columns: 2,
+                rows: 3,
+            },
+        ] {
+            viewport.sampling = sampling;
+            let serial = viewport.rasterize_with_threads(&scene, 1);
+            for threads in [2, 3, 8, 100] {
+                assert_eq!(viewport.rasterize_with_threads(&scene, threads), serial);
             }
-            writeln!(buf)?;
         }
-        write!(buf, "{}", Color::Reset)
+    }
+
+    #[test]
+    fn supersampling() {
+        // The front of the cube covers the right three quarters of the middle column and
+        // the left quarter of the last one.
+        let mut viewport = Viewport::new(3, 1, Camera::new(0.5, 0.6, 0.2));
+        viewport.sampling = Sampling::Grid {
+            columns: 4,
+            rows: 1,
+        };
+        let scene = cube().transform(Translation(Vector(0.25, 3.0, 0.0)));
+        let framebuffer = viewport.rasterize(scene);
+        let cell = framebuffer.get(1, 0).unwrap();
+        check_about(cell.coverage(), 0.75);
+        assert_eq!(cell.color, Some(Color::RED));
+        check_about(framebuffer.get(2, 0).unwrap().coverage(), 0.25);
+        assert_eq!(*framebuffer.get(0, 0).unwrap(), Cell::EMPTY);
+    }
+
+    #[test]
+    fn background() {
+        let mut viewport = Viewport::new(3, 1, Camera::new(0.5, 0.6, 0.2));
+        viewport.sampling = Sampling::Grid {
+            columns: 4,
+            rows: 1,
+        };
+        let scene = cube()
+            .transform(Translation(Vector(0.25, 3.0, 0.0)))
+            .background(Color::BLUE);
+        let framebuffer = viewport.rasterize(scene);
+        assert_eq!(framebuffer.get(0, 0).unwrap().color, Some(Color::BLUE));
+        check_about(framebuffer.get(2, 0).unwrap().coverage(), 1.0);
+        // Left out, the background is not hit, and edges blend with whatever is behind.
+        viewport.background = false;
+        let framebuffer = viewport.rasterize(scene);
+        assert_eq!(*framebuffer.get(0, 0).unwrap(), Cell::EMPTY);
+        let edge = framebuffer.get(2, 0).unwrap();
+        check_about(edge.coverage(), 0.25);
+        assert_eq!(edge.color, Some(Color::RED));
     }
 }
@@ -0,0 +1,182 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{Description, Options, Scene, SceneError};
+
+/// Returns when the file at `path` was last modified, if it can be told.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The scene of a run, reloaded from its scene file whenever the file or any image it
+/// reads is modified.
+pub(crate) struct Watcher {
+    path: Option<PathBuf>,
+    /// When the scene file and then the files it read were modified when they were last
+    /// loaded.
+    modified: Vec<Option<SystemTime>>,
+    scene: Scene,
+    error: Option<SceneError>,
+}
+
+impl Watcher {
+    /// Returns a watcher of the scene file of `options`, or of their scene if they have
+    /// no scene file, which never changes, failing if the scene file cannot be loaded to
+    /// begin with.
+    pub(crate) fn new(options: &Options) -> Result<Self, SceneError> {
+        let mut watcher = Self {
+            path: options.scene_file.clone(),
+            modified: Vec::new(),
+            scene: options.scene.clone(),
+            error: None,
+        };
+        if let Some(path) = options.scene_file.as_deref() {
+            watcher.scene = Scene::Described(Arc::new(Description::load(path)?));
+            watcher.modified = watcher.modified(path);
+        }
+        Ok(watcher)
+    }
+
+    /// Returns when the scene file and then the files that the last scene loaded from
+    /// it read were modified.
+    fn modified(&self, path: &Path) -> Vec<Option<SystemTime>> {
+        let files = match &self.scene {
+            Scene::Described(description) => description.files(),
+            _ => &[],
+        };
+        std::iter::once(path)
+            .chain(files.iter().map(PathBuf::as_path))
+            .map(modified)
+            .collect()
+    }
+
+    /// Reloads the scene file if it or a file it read, such as the image of a texture,
+    /// has been modified since it was last read, keeping the previous scene if the file
+    /// cannot be read or parsed, and returns whether the scene changed.
+    ///
+    /// Images are watched as named by the last scene that loaded, so an image that only
+    /// a broken edit of the scene file names is read again once the file is saved again.
+    pub(crate) fn poll(&mut self) -> bool {
+        let Some(path) = self.path.clone() else {
+            return false;
+        };
+        let modified = self.modified(&path);
+        if modified[0].is_some() && modified == self.modified {
+            return false;
+        }
+        match Description::load(&path) {
+            Ok(description) => {
+                self.scene = Scene::Described(Arc::new(description));
+                self.modified = self.modified(&path);
+                self.error = None;
+                true
+            }
+            Err(error) => {
+                self.modified = modified;
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+
+    /// Returns the last scene that was loaded.
+    pub(crate) fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    /// Returns why the scene file could not be loaded the last time it was read.
+    pub(crate) fn error(&self) -> Option<&SceneError> {
+        self.error.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        time::{Duration, SystemTime},
+    };
+
+    use super::Watcher;
+    use crate::{Options, Scene};
+
+    #[test]
+    fn reloads() {
+        let path = env::temp_dir().join(format!("shapes-watch-{}.scene", std::process::id()));
+        fs::write(&path, "cube\ncolor red\n").unwrap();
+        let options = Options {
+            scene_file: Some(path.clone()),
+            ..Options::default()
+        };
+        let mut watcher = Watcher::new(&options).unwrap();
+        assert!(watcher.error().is_none());
+        let Scene::Described(first) = watcher.scene().clone() else {
+            panic!("expected a described scene");
+        };
+
+        // Modification times can be coarse, so the file is dated to make it change.
+        let touch = |text: &str, seconds| {
+            fs::write(&path, text).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        };
+        touch("cube\ncolor bread\n", 1);
+        assert!(!watcher.poll());
+        assert_eq!(
+            watcher.error().unwrap().to_string(),
+            format!("{}: line 2: unknown color `bread`", path.display())
+        );
+        assert_eq!(*watcher.scene(), Scene::Described(first.clone()));
+
+        touch("torus 1 0.5\n", 2);
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+        assert!(watcher.error().is_none());
+        assert_ne!(*watcher.scene(), Scene::Described(first));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn fails_to_start() {
+        let options = Options {
+            scene_file: Some(env::temp_dir().join("shapes-watch-missing.scene")),
+            ..Options::default()
+        };
+        assert!(Watcher::new(&options).is_err());
+    }
+
+    #[test]
+    fn reloads_images() {
+        let directory = env::temp_dir().join(format!("shapes-watch-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let image = directory.join("red.ppm");
+        let path = directory.join("textured.scene");
+        fs::write(&image, "P3 1 1 255 255 0 0").unwrap();
+        fs::write(&path, "cube\ntexture image red.ppm\n").unwrap();
+        let options = Options {
+            scene_file: Some(path.clone()),
+            ..Options::default()
+        };
+        let mut watcher = Watcher::new(&options).unwrap();
+        assert!(watcher.error().is_none());
+        assert!(!watcher.poll());
+        let first = watcher.scene().clone();
+
+        // Editing the image reloads the scene, though the scene file is the same.
+        fs::write(&image, "P3 1 1 255 0 0 255").unwrap();
+        let file = fs::File::options().write(true).open(&image).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap();
+        assert!(watcher.poll());
+        assert_ne!(*watcher.scene(), first);
+        assert!(!watcher.poll());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
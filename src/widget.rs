@@ -0,0 +1,79 @@
+use std::io;
+
+use crate::{
+    encode::ansi, terminal_viewport, ColorMode, Framebuffer, Hittable, RenderMode, Sampling,
+    Viewport,
+};
+
+/// A rectangle of a terminal that scenes are drawn into, such as part of a dashboard.
+///
+/// Drawing leaves everything outside of the rectangle untouched, and so are the cells
+/// inside of it where nothing was hit, or only the background of a scene if it is left
+/// out. Between frames of an animation, the host should redraw its own content under the
+/// widget, since cells that are no longer hit are not cleared.
+#[derive(Copy, Clone, Debug)]
+pub struct Widget {
+    /// The column of the left edge of this widget, counting from 0.
+    pub column: usize,
+    /// The line of the top edge of this widget, counting from 0.
+    pub line: usize,
+    /// The number of columns of this widget.
+    pub width: usize,
+    /// The number of lines of this widget.
+    pub height: usize,
+    /// The characters to draw cells with.
+    pub render_mode: RenderMode,
+    /// The colors to draw cells with.
+    pub color_mode: ColorMode,
+    /// Where within each cell rays are cast.
+    pub sampling: Sampling,
+    /// Whether the [background](crate::HittableExt::background) of a scene is drawn, or
+    /// left out so that what is under the widget shows through.
+    pub background: bool,
+}
+
+impl Widget {
+    /// Returns a widget of `width` by `height` cells with its top-left cell at `column`
+    /// and `line`, drawn with the default modes and sampling, and with backgrounds.
+    pub fn new(column: usize, line: usize, width: usize, height: usize) -> Self {
+        Self {
+            column,
+            line,
+            width,
+            height,
+            render_mode: RenderMode::default(),
+            color_mode: ColorMode::default(),
+            sampling: Sampling::default(),
+            background: true,
+        }
+    }
+
+    /// Returns the viewport that scenes are rasterized through, which has twice as many
+    /// rows as this widget has lines when drawn with half blocks.
+    pub fn viewport(&self) -> Viewport {
+        Viewport {
+            sampling: self.sampling,
+            background: self.background,
+            ..terminal_viewport(self.width, self.height, self.render_mode)
+        }
+    }
+
+    /// Renders `scene` into a framebuffer to be [overlaid](Framebuffer::overlay) on a
+    /// caller's grid of cells at this widget's position.
+    pub fn rasterize(&self, scene: impl Hittable + Sync) -> Framebuffer {
+        self.viewport().rasterize(scene)
+    }
+
+    /// Draws `scene` at this widget's position of the terminal with absolute cursor
+    /// moves, leaving the cursor after the last character drawn.
+    pub fn draw(&self, buf: impl io::Write, scene: impl Hittable + Sync) -> io::Result<()> {
+        ansi::encode_at(
+            buf,
+            &self.rasterize(scene),
+            self.column,
+            self.line,
+            self.render_mode,
+            self.color_mode,
+        )
+    }
+}